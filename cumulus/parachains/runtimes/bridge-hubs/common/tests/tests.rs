@@ -46,7 +46,7 @@ fn test_deny_export_message_from() {
 				&origin,
 				&mut xcm,
 				Weight::zero(),
-				&mut Properties { weight_credit: Weight::zero(), message_id: None }
+				&mut Properties::new(Weight::zero(), None)
 			),
 			expected_result
 		);
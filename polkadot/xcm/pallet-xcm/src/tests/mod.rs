@@ -619,7 +619,7 @@ fn basic_subscription_works() {
 			&remote,
 			message.inner_mut(),
 			weight,
-			&mut Properties { weight_credit: Weight::zero(), message_id: None },
+			&mut Properties::new(Weight::zero(), None),
 		));
 	});
 }
@@ -0,0 +1,195 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Derive macros implementing `ShouldExecute` and `DenyExecution` for structs whose fields are
+//! sub-barriers, and the `prioritized!` macro ordering barriers by priority.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use proc_macro_crate::{crate_name, FoundCrate};
+use quote::quote;
+use syn::{
+	parse::{Parse, ParseStream},
+	punctuated::Punctuated,
+	Data, DeriveInput, Error, LitInt, Meta, Result, Token, Type,
+};
+
+/// The barrier trait to derive.
+pub enum Barrier {
+	ShouldExecute,
+	DenyExecution,
+}
+
+pub fn derive(input: DeriveInput, barrier: Barrier) -> Result<TokenStream2> {
+	let data_struct = match &input.data {
+		Data::Struct(data_struct) => data_struct,
+		_ => return Err(Error::new_spanned(&input, "Expected a struct")),
+	};
+	let barriers = data_struct
+		.fields
+		.iter()
+		.filter_map(|field| match is_skipped(&field.attrs) {
+			Ok(true) => None,
+			Ok(false) => Some(Ok(&field.ty)),
+			Err(error) => Some(Err(error)),
+		})
+		.collect::<Result<Vec<&Type>>>()?;
+
+	let executor = executor_crate()?;
+	let name = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+	let mut where_clause = where_clause.cloned().unwrap_or_else(|| syn::parse_quote!(where));
+	let output = match barrier {
+		Barrier::ShouldExecute => {
+			for ty in &barriers {
+				where_clause
+					.predicates
+					.push(syn::parse_quote!(#ty: #executor::traits::ShouldExecute));
+			}
+			quote! {
+				impl #impl_generics #executor::traits::ShouldExecute for #name #ty_generics
+				#where_clause
+				{
+					fn should_execute<RuntimeCall>(
+						origin: &#executor::__private::Location,
+						instructions: &mut [#executor::__private::Instruction<RuntimeCall>],
+						max_weight: #executor::__private::Weight,
+						properties: &mut #executor::traits::Properties,
+					) -> ::core::result::Result<(), #executor::__private::ProcessMessageError> {
+						<( #( #barriers, )* ) as #executor::traits::ShouldExecute>::should_execute(
+							origin,
+							instructions,
+							max_weight,
+							properties,
+						)
+					}
+
+					fn names(out: &mut #executor::__private::Vec<&'static str>) {
+						<( #( #barriers, )* ) as #executor::traits::ShouldExecute>::names(out)
+					}
+
+					fn barrier_weight<RuntimeCall>(
+						instructions: &[#executor::__private::Instruction<RuntimeCall>],
+					) -> #executor::__private::Weight {
+						<( #( #barriers, )* ) as #executor::traits::ShouldExecute>::barrier_weight(
+							instructions,
+						)
+					}
+				}
+			}
+		},
+		Barrier::DenyExecution => {
+			for ty in &barriers {
+				where_clause
+					.predicates
+					.push(syn::parse_quote!(#ty: #executor::traits::DenyExecution));
+			}
+			quote! {
+				impl #impl_generics #executor::traits::DenyExecution for #name #ty_generics
+				#where_clause
+				{
+					fn deny_execution<RuntimeCall>(
+						origin: &#executor::__private::Location,
+						instructions: &mut [#executor::__private::Instruction<RuntimeCall>],
+						max_weight: #executor::__private::Weight,
+						properties: &mut #executor::traits::Properties,
+					) -> ::core::result::Result<(), #executor::__private::ProcessMessageError> {
+						<( #( #barriers, )* ) as #executor::traits::DenyExecution>::deny_execution(
+							origin,
+							instructions,
+							max_weight,
+							properties,
+						)
+					}
+
+					fn barrier_weight<RuntimeCall>(
+						instructions: &[#executor::__private::Instruction<RuntimeCall>],
+					) -> #executor::__private::Weight {
+						<( #( #barriers, )* ) as #executor::traits::DenyExecution>::barrier_weight(
+							instructions,
+						)
+					}
+				}
+			}
+		},
+	};
+	Ok(output)
+}
+
+/// Returns the path to the XCM executor crate, through which all generated paths are resolved.
+///
+/// The crate is found by its package name, whether renamed or not, or else within the
+/// `polkadot-sdk` umbrella crate. Within the executor crate itself, paths resolve from `crate`.
+fn executor_crate() -> Result<syn::Path> {
+	const EXECUTOR: &str = "staging-xcm-executor";
+	let path = match crate_name(EXECUTOR) {
+		Ok(FoundCrate::Itself) => "crate".into(),
+		Ok(FoundCrate::Name(name)) => format!("::{name}"),
+		Err(_) => match crate_name("polkadot-sdk") {
+			Ok(FoundCrate::Name(sdk)) => format!("::{sdk}::staging_xcm_executor"),
+			_ => return Err(Error::new(Span::call_site(), format!("`{EXECUTOR}` not found"))),
+		},
+	};
+	syn::parse_str(&path)
+}
+
+/// Returns whether the field is annotated with `#[barrier(skip)]`, i.e. is not a sub-barrier.
+fn is_skipped(attrs: &[syn::Attribute]) -> Result<bool> {
+	let mut skipped = false;
+	for attr in attrs.iter().filter(|attr| attr.path().is_ident("barrier")) {
+		let Meta::List(ref list) = attr.meta else {
+			return Err(Error::new_spanned(attr, "Expected `barrier(skip)`"))
+		};
+		let inner_ident: syn::Ident = syn::parse2(list.tokens.clone())
+			.map_err(|_| Error::new_spanned(attr, "Expected `barrier(skip)`"))?;
+		if inner_ident != "skip" {
+			return Err(Error::new_spanned(attr, "Expected `barrier(skip)`"))
+		}
+		skipped = true;
+	}
+	Ok(skipped)
+}
+
+/// A barrier annotated with its priority, i.e. `priority => Barrier`.
+struct PrioritizedBarrier {
+	priority: u32,
+	barrier: Type,
+}
+
+impl Parse for PrioritizedBarrier {
+	fn parse(input: ParseStream) -> Result<Self> {
+		let priority = input.parse::<LitInt>()?.base10_parse()?;
+		input.parse::<Token![=>]>()?;
+		let barrier = input.parse()?;
+		Ok(Self { priority, barrier })
+	}
+}
+
+/// A comma-separated list of prioritized barriers.
+pub struct PrioritizedBarriers(Punctuated<PrioritizedBarrier, Token![,]>);
+
+impl Parse for PrioritizedBarriers {
+	fn parse(input: ParseStream) -> Result<Self> {
+		Ok(Self(Punctuated::parse_terminated(input)?))
+	}
+}
+
+pub fn prioritized(input: PrioritizedBarriers) -> TokenStream2 {
+	let mut barriers = input.0.into_iter().collect::<Vec<_>>();
+	// Stable, so barriers of equal priority keep their declaration order.
+	barriers.sort_by(|a, b| b.priority.cmp(&a.priority));
+	let barriers = barriers.iter().map(|prioritized| &prioritized.barrier);
+	quote!(( #( #barriers, )* ))
+}
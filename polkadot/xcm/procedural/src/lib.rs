@@ -19,6 +19,7 @@
 use proc_macro::TokenStream;
 use syn::{parse_macro_input, DeriveInput};
 
+mod barriers;
 mod builder_pattern;
 mod enum_variants;
 mod v3;
@@ -95,3 +96,40 @@ pub fn derive_num_variants(input: TokenStream) -> TokenStream {
 		.unwrap_or_else(syn::Error::into_compile_error)
 		.into()
 }
+
+/// Implements `ShouldExecute` for a struct whose fields are sub-barriers, passing if any of them
+/// passes, exactly like the tuple of the field types.
+///
+/// Fields which are not sub-barriers (e.g. `PhantomData`) must be annotated with
+/// `#[barrier(skip)]`.
+#[proc_macro_derive(ShouldExecute, attributes(barrier))]
+pub fn derive_should_execute(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	barriers::derive(input, barriers::Barrier::ShouldExecute)
+		.unwrap_or_else(syn::Error::into_compile_error)
+		.into()
+}
+
+/// Implements `DenyExecution` for a struct whose fields are sub-barriers, denying if any of them
+/// denies, exactly like the tuple of the field types.
+///
+/// Fields which are not sub-barriers (e.g. `PhantomData`) must be annotated with
+/// `#[barrier(skip)]`.
+#[proc_macro_derive(DenyExecution, attributes(barrier))]
+pub fn derive_deny_execution(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	barriers::derive(input, barriers::Barrier::DenyExecution)
+		.unwrap_or_else(syn::Error::into_compile_error)
+		.into()
+}
+
+/// Expands to a tuple of barriers ordered by explicit priority, highest first, from a list of
+/// `priority => Barrier` entries. Barriers of equal priority keep their declaration order.
+///
+/// E.g. `prioritized!(10 => A, 20 => B, 10 => C)` expands to `(B, A, C)`, whose elements are
+/// evaluated from left to right.
+#[proc_macro]
+pub fn prioritized(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as barriers::PrioritizedBarriers);
+	barriers::prioritized(input).into()
+}
@@ -0,0 +1,43 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Test the tuple generated by the `prioritized!` macro.
+
+use core::{any::TypeId, marker::PhantomData};
+use xcm_procedural::prioritized;
+
+struct A;
+struct B;
+struct C;
+struct D<T>(PhantomData<T>);
+
+#[test]
+fn prioritized_orders_by_descending_priority() {
+	type Barrier = prioritized!(10 => A, 30 => B, 20 => D<C>);
+	assert_eq!(TypeId::of::<Barrier>(), TypeId::of::<(B, D<C>, A)>());
+}
+
+#[test]
+fn prioritized_keeps_declaration_order_of_equal_priorities() {
+	type Barrier = prioritized!(5 => A, 5 => B, 7 => C, 5 => D<A>,);
+	assert_eq!(TypeId::of::<Barrier>(), TypeId::of::<(C, A, B, D<A>)>());
+}
+
+#[test]
+fn prioritized_single_barrier_is_a_tuple() {
+	type Barrier = prioritized!(1 => A);
+	assert_eq!(TypeId::of::<Barrier>(), TypeId::of::<(A,)>());
+}
@@ -76,6 +76,11 @@ impl<T> DoubleEncoded<T> {
 	pub fn into_encoded(self) -> Vec<u8> {
 		self.encoded
 	}
+
+	/// Access the encoded data by reference.
+	pub fn encoded(&self) -> &[u8] {
+		&self.encoded
+	}
 }
 
 impl<T: Decode> DoubleEncoded<T> {
@@ -133,4 +138,14 @@ mod tests {
 		let encoded: DoubleEncoded<_> = Encode::encode(&val).into();
 		assert_eq!(encoded.try_into(), Ok(val));
 	}
+
+	#[test]
+	fn encoded_works() {
+		let val: u64 = 42;
+		let mut encoded: DoubleEncoded<u64> = Encode::encode(&val).into();
+		assert_eq!(encoded.encoded(), &Encode::encode(&val)[..]);
+		// decoding does not affect the encoded data
+		assert_eq!(encoded.ensure_decoded(), Ok(&val));
+		assert_eq!(encoded.encoded(), &Encode::encode(&val)[..]);
+	}
 }
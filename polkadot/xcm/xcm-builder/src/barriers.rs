@@ -17,14 +17,25 @@
 //! Various implementations for `ShouldExecute`.
 
 use crate::{CreateMatcher, MatchXcm};
+use alloc::vec::Vec;
+use codec::Encode;
 use core::{cell::Cell, marker::PhantomData, ops::ControlFlow, result::Result};
 use frame_support::{
 	ensure,
-	traits::{Contains, Get, ProcessMessageError},
+	traits::{Contains, ContainsPair, Get, ProcessMessageError, TypedGet},
 };
 use polkadot_parachain_primitives::primitives::IsSystem;
+use sp_runtime::traits::{AtLeast32BitUnsigned, Convert, Hash as HashT, Saturating};
 use xcm::prelude::*;
-use xcm_executor::traits::{CheckSuspension, DenyExecution, OnResponse, Properties, ShouldExecute};
+#[cfg(feature = "std")]
+use xcm_executor::traits::record_barrier_decision;
+use xcm_executor::{
+	traits::{
+		BarrierError, BarrierEvent, CheckSuspension, DenyExecution, OnResponse, Properties,
+		ShouldExecute, WeightBounds,
+	},
+	AssetsInHolding,
+};
 
 /// Execution barrier that just takes `max_weight` from `properties.weight_credit`.
 ///
@@ -214,6 +225,14 @@ impl<InnerBarrier: ShouldExecute, LocalUniversal: Get<InteriorLocation>, MaxPref
 			properties,
 		)
 	}
+
+	fn names(out: &mut Vec<&'static str>) {
+		InnerBarrier::names(out)
+	}
+
+	fn barrier_weight<Call>(instructions: &[Instruction<Call>]) -> Weight {
+		InnerBarrier::barrier_weight(instructions)
+	}
 }
 
 /// Sets the message ID to `t` using a `SetTopic(t)` in the last position if present.
@@ -243,6 +262,14 @@ impl<InnerBarrier: ShouldExecute> ShouldExecute for TrailingSetTopicAsId<InnerBa
 		};
 		InnerBarrier::should_execute(&origin, &mut instructions[..until], max_weight, properties)
 	}
+
+	fn names(out: &mut Vec<&'static str>) {
+		InnerBarrier::names(out)
+	}
+
+	fn barrier_weight<Call>(instructions: &[Instruction<Call>]) -> Weight {
+		InnerBarrier::barrier_weight(instructions)
+	}
 }
 
 /// Barrier condition that allows for a `SuspensionChecker` that controls whether or not the XCM
@@ -265,6 +292,165 @@ where
 			Inner::should_execute(origin, instructions, max_weight, properties)
 		}
 	}
+
+	fn names(out: &mut Vec<&'static str>) {
+		Inner::names(out)
+	}
+
+	fn barrier_weight<Call>(instructions: &[Instruction<Call>]) -> Weight {
+		Inner::barrier_weight(instructions)
+	}
+}
+
+/// Barrier wrapper which records the verdict of `Inner` in the barrier decision trace of
+/// [`xcm_executor::traits::trace_barrier_decisions`], so that dry-run tooling can expose it.
+///
+/// Barrier tuples already record the verdicts of their elements; this makes any other barrier,
+/// e.g. a single barrier or a wrapper, show up in the trace as well. Only available with the `std`
+/// feature.
+#[cfg(feature = "std")]
+pub struct TracingShouldExecute<Inner>(PhantomData<Inner>);
+#[cfg(feature = "std")]
+impl<Inner: ShouldExecute> ShouldExecute for TracingShouldExecute<Inner> {
+	fn should_execute<Call>(
+		origin: &Location,
+		instructions: &mut [Instruction<Call>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		let result = Inner::should_execute(origin, instructions, max_weight, properties);
+		record_barrier_decision(core::any::type_name::<Inner>(), result);
+		result
+	}
+
+	fn names(out: &mut Vec<&'static str>) {
+		Inner::names(out)
+	}
+
+	fn barrier_weight<Call>(instructions: &[Instruction<Call>]) -> Weight {
+		Inner::barrier_weight(instructions)
+	}
+}
+
+/// Barrier wrapper which converts a panic of `Inner` into a rejection with `Unsupported`, logging
+/// a warning, rather than aborting message processing.
+///
+/// This is a diagnostic and testing aid for custom barriers, only available with the `std`
+/// feature. Panics cannot be caught when compiled with `panic = "abort"`, and `properties` may be
+/// left partially modified by the panicking barrier.
+#[cfg(feature = "std")]
+pub struct CatchPanics<Inner>(PhantomData<Inner>);
+#[cfg(feature = "std")]
+impl<Inner: ShouldExecute> ShouldExecute for CatchPanics<Inner> {
+	fn should_execute<Call>(
+		origin: &Location,
+		instructions: &mut [Instruction<Call>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+			Inner::should_execute(origin, instructions, max_weight, properties)
+		}))
+		.unwrap_or_else(|_| {
+			log::warn!(
+				target: "xcm::barriers",
+				"CatchPanics barrier {} panicked, origin: {:?}, max_weight: {:?}",
+				core::any::type_name::<Inner>(), origin, max_weight,
+			);
+			Err(ProcessMessageError::Unsupported)
+		})
+	}
+
+	fn names(out: &mut Vec<&'static str>) {
+		Inner::names(out)
+	}
+
+	fn barrier_weight<Call>(instructions: &[Instruction<Call>]) -> Weight {
+		Inner::barrier_weight(instructions)
+	}
+}
+
+/// Barrier wrapper which evaluates `B` on a worker thread, rejecting the message with
+/// `Unsupported` if `B` does not decide within `Millis` milliseconds.
+///
+/// **This is non-deterministic and must not be used on-chain.** It is meant for off-chain
+/// simulators and tests evaluating untrusted barriers, and is only available with the `std`
+/// feature. `B` sees a copy of the message with `()` as call type, so it cannot decode the calls of
+/// `Transact`; changes it makes to the message and `properties` are copied back unless it fails.
+///
+/// The worker thread runs without the externalities of the calling thread, so `B` must not access
+/// storage or other host functions: doing so panics, which rejects the message like any other
+/// panic of `B`. Threads cannot be cancelled, hence a worker which overruns is left running
+/// detached until `B` returns.
+#[cfg(feature = "std")]
+pub struct TimeBounded<B, Millis>(PhantomData<(B, Millis)>);
+#[cfg(feature = "std")]
+impl<B: ShouldExecute + 'static, Millis: Get<u64>> ShouldExecute for TimeBounded<B, Millis> {
+	fn should_execute<Call>(
+		origin: &Location,
+		instructions: &mut [Instruction<Call>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		let mut program: Vec<Instruction<()>> =
+			instructions.iter().cloned().map(Instruction::into).collect();
+		let (worker_origin, mut worker_properties) = (origin.clone(), properties.clone());
+		let (sender, receiver) = std::sync::mpsc::channel();
+		let spawned =
+			std::thread::Builder::new()
+				.name("xcm-time-bounded-barrier".into())
+				.spawn(move || {
+					let result = B::should_execute(
+						&worker_origin,
+						&mut program,
+						max_weight,
+						&mut worker_properties,
+					);
+					let _ = sender.send((result, program, worker_properties));
+				});
+		if let Err(error) = spawned {
+			log::warn!(
+				target: "xcm::barriers",
+				"TimeBounded barrier {} could not be spawned: {:?}",
+				core::any::type_name::<B>(), error,
+			);
+			return Err(ProcessMessageError::Unsupported)
+		}
+		match receiver.recv_timeout(std::time::Duration::from_millis(Millis::get())) {
+			Ok((result, program, worker_properties)) => {
+				for (instruction, evaluated) in instructions.iter_mut().zip(program) {
+					*instruction = evaluated.into();
+				}
+				*properties = worker_properties;
+				result
+			},
+			Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
+				log::warn!(
+					target: "xcm::barriers",
+					"TimeBounded barrier {} overran {} ms, origin: {:?}, max_weight: {:?}",
+					core::any::type_name::<B>(), Millis::get(), origin, max_weight,
+				);
+				Err(ProcessMessageError::Unsupported)
+			},
+			// The worker only hangs up without a verdict if `B` panicked.
+			Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => {
+				log::warn!(
+					target: "xcm::barriers",
+					"TimeBounded barrier {} panicked, origin: {:?}, max_weight: {:?}",
+					core::any::type_name::<B>(), origin, max_weight,
+				);
+				Err(ProcessMessageError::Unsupported)
+			},
+		}
+	}
+
+	fn names(out: &mut Vec<&'static str>) {
+		B::names(out)
+	}
+
+	fn barrier_weight<Call>(instructions: &[Instruction<Call>]) -> Weight {
+		B::barrier_weight(instructions)
+	}
 }
 
 /// Allows execution from any origin that is contained in `T` (i.e. `T::Contains(origin)`).
@@ -442,6 +628,11 @@ impl ShouldExecute for AllowHrmpNotificationsFromRelayChain {
 
 /// Deny executing the XCM if it matches any of the Deny filter regardless of anything else.
 /// If it passes the Deny, and matches one of the Allow cases then it is let through.
+///
+/// To migrate a barrier tuple mixing deny-style checks into its allow cases, move the
+/// `DenyExecution` barriers into `Deny` and keep the `ShouldExecute` barriers in `Allow`, e.g.
+/// `DenyThenTry<(DenyTrap, DenyEmpty), (TakeWeightCredit, AllowUnpaidExecutionFrom<T>)>`. A
+/// barrier implementing only `ShouldExecute` fails to compile as part of `Deny`.
 pub struct DenyThenTry<Deny, Allow>(PhantomData<Deny>, PhantomData<Allow>)
 where
 	Deny: DenyExecution,
@@ -461,6 +652,14 @@ where
 		Deny::deny_execution(origin, message, max_weight, properties)?;
 		Allow::should_execute(origin, message, max_weight, properties)
 	}
+
+	fn names(out: &mut Vec<&'static str>) {
+		Allow::names(out)
+	}
+
+	fn barrier_weight<Call>(instructions: &[Instruction<Call>]) -> Weight {
+		Deny::barrier_weight(instructions).saturating_add(Allow::barrier_weight(instructions))
+	}
 }
 
 // See issue <https://github.com/paritytech/polkadot/issues/5233>
@@ -502,3 +701,3172 @@ impl DenyExecution for DenyReserveTransferToRelayChain {
 		Ok(())
 	}
 }
+
+/// Barrier condition which checks that any leading `DescendOrigin` instructions only descend into
+/// interior locations which `Policy` permits, and then evaluates `InnerBarrier`.
+///
+/// Each leading `DescendOrigin(interior)` is accepted only if `Policy::contains(origin, interior)`,
+/// where `origin` is the origin computed by the preceding descents. This allows e.g. restricting a
+/// parachain to descending into its own sub-identities.
+pub struct RestrictDescend<Policy, InnerBarrier>(PhantomData<(Policy, InnerBarrier)>);
+impl<Policy: ContainsPair<Location, InteriorLocation>, InnerBarrier: ShouldExecute> ShouldExecute
+	for RestrictDescend<Policy, InnerBarrier>
+{
+	fn should_execute<Call>(
+		origin: &Location,
+		instructions: &mut [Instruction<Call>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"RestrictDescend origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		let mut actual_origin = origin.clone();
+		for inst in instructions.iter() {
+			let DescendOrigin(interior) = inst else { break };
+			ensure!(Policy::contains(&actual_origin, interior), ProcessMessageError::Unsupported);
+			actual_origin
+				.append_with(interior.clone())
+				.map_err(|_| ProcessMessageError::Unsupported)?;
+		}
+		InnerBarrier::should_execute(origin, instructions, max_weight, properties)
+	}
+
+	fn names(out: &mut Vec<&'static str>) {
+		InnerBarrier::names(out)
+	}
+
+	fn barrier_weight<Call>(instructions: &[Instruction<Call>]) -> Weight {
+		InnerBarrier::barrier_weight(instructions)
+	}
+}
+
+/// Returns the program carried by a forwarding instruction, to be executed on another chain.
+fn forwarded_xcm<Call>(inst: &Instruction<Call>) -> Option<&Xcm<()>> {
+	match inst {
+		DepositReserveAsset { xcm, .. } |
+		InitiateReserveWithdraw { xcm, .. } |
+		InitiateTeleport { xcm, .. } |
+		TransferReserveAsset { xcm, .. } |
+		ExportMessage { xcm, .. } => Some(xcm),
+		InitiateTransfer { remote_xcm, .. } => Some(remote_xcm),
+		_ => None,
+	}
+}
+
+/// Returns the destination of a forwarding instruction.
+fn forward_destination<Call>(inst: &Instruction<Call>) -> Option<&Location> {
+	match inst {
+		DepositReserveAsset { dest, .. } |
+		InitiateTeleport { dest, .. } |
+		TransferReserveAsset { dest, .. } => Some(dest),
+		InitiateReserveWithdraw { reserve, .. } => Some(reserve),
+		InitiateTransfer { destination, .. } => Some(destination),
+		_ => None,
+	}
+}
+
+/// Returns the program nested in an instruction which is executed locally, e.g. an error handler.
+fn local_xcm<Call>(inst: &Instruction<Call>) -> Option<&Xcm<Call>> {
+	match inst {
+		SetErrorHandler(xcm) | SetAppendix(xcm) | ExecuteWithOrigin { xcm, .. } => Some(xcm),
+		_ => None,
+	}
+}
+
+/// Calls `f` with the id of every asset referenced by `inst`, including the ids of wildcards.
+fn for_each_asset_id<Call>(inst: &Instruction<Call>, f: &mut impl FnMut(&AssetId)) {
+	fn filter(filter: &AssetFilter, f: &mut impl FnMut(&AssetId)) {
+		match filter {
+			AssetFilter::Definite(assets) => assets.inner().iter().for_each(|asset| f(&asset.id)),
+			AssetFilter::Wild(AllOf { id, .. } | AllOfCounted { id, .. }) => f(id),
+			AssetFilter::Wild(_) => {},
+		}
+	}
+	match inst {
+		WithdrawAsset(assets) |
+		ReserveAssetDeposited(assets) |
+		ReceiveTeleportedAsset(assets) |
+		TransferAsset { assets, .. } |
+		TransferReserveAsset { assets, .. } |
+		ClaimAsset { assets, .. } |
+		BurnAsset(assets) |
+		ExpectAsset(assets) => assets.inner().iter().for_each(|asset| f(&asset.id)),
+		BuyExecution { fees: asset, .. } | PayFees { asset } => f(&asset.id),
+		DepositAsset { assets, .. } |
+		DepositReserveAsset { assets, .. } |
+		InitiateReserveWithdraw { assets, .. } |
+		InitiateTeleport { assets, .. } => filter(assets, f),
+		InitiateTransfer { remote_fees, assets, .. } => remote_fees
+			.iter()
+			.chain(assets.iter())
+			.for_each(|transfer_filter| filter(transfer_filter.inner(), f)),
+		_ => {},
+	}
+}
+
+/// Calls `f` with the interior of every location referenced by `inst`, including the ids of the
+/// assets it references. Nested programs are not considered.
+fn for_each_interior<Call>(inst: &Instruction<Call>, f: &mut impl FnMut(&Junctions)) {
+	fn filter(filter: &AssetFilter, f: &mut impl FnMut(&Junctions)) {
+		match filter {
+			Definite(assets) => assets.inner().iter().for_each(|asset| f(asset.id.0.interior())),
+			Wild(AllOf { id, .. } | AllOfCounted { id, .. }) => f(id.0.interior()),
+			Wild(_) => {},
+		}
+	}
+	for_each_asset_id(inst, &mut |id| f(id.0.interior()));
+	match inst {
+		QueryResponse { querier: Some(location), .. } |
+		TransferAsset { beneficiary: location, .. } |
+		TransferReserveAsset { dest: location, .. } |
+		DepositAsset { beneficiary: location, .. } |
+		DepositReserveAsset { dest: location, .. } |
+		InitiateReserveWithdraw { reserve: location, .. } |
+		InitiateTeleport { dest: location, .. } |
+		ClaimAsset { ticket: location, .. } |
+		ExpectOrigin(Some(location)) |
+		AliasOrigin(location) |
+		UnpaidExecution { check_origin: Some(location), .. } |
+		InitiateTransfer { destination: location, .. } => f(location.interior()),
+		ReportError(response_info) |
+		ReportTransactStatus(response_info) |
+		QueryPallet { response_info, .. } => f(response_info.destination.interior()),
+		ReportHolding { response_info, assets } => {
+			f(response_info.destination.interior());
+			filter(assets, f);
+		},
+		ExchangeAsset { give, want, .. } => {
+			filter(give, f);
+			want.inner().iter().for_each(|asset| f(asset.id.0.interior()));
+		},
+		LockAsset { asset, unlocker: location } |
+		UnlockAsset { asset, target: location } |
+		NoteUnlockable { asset, owner: location } |
+		RequestUnlock { asset, locker: location } => {
+			f(asset.id.0.interior());
+			f(location.interior());
+		},
+		DescendOrigin(interior) |
+		ExportMessage { destination: interior, .. } |
+		ExecuteWithOrigin { descendant_origin: Some(interior), .. } => f(interior),
+		SetHints { hints } => hints.iter().for_each(|hint| match hint {
+			AssetClaimer { location } => f(location.interior()),
+		}),
+		_ => {},
+	}
+}
+
+/// Returns whether any instruction within `instructions`, including those of nested programs which
+/// are executed locally, satisfies `f`.
+fn any_local_instruction<Call>(
+	instructions: &[Instruction<Call>],
+	f: &impl Fn(&Instruction<Call>) -> bool,
+) -> bool {
+	instructions.iter().any(|inst| {
+		f(inst) || local_xcm(inst).map_or(false, |xcm| any_local_instruction(&xcm.0, f))
+	})
+}
+
+/// Returns the deepest nesting of programs carried by forwarding instructions within
+/// `instructions`.
+fn forward_hops<Call>(instructions: &[Instruction<Call>]) -> u8 {
+	instructions
+		.iter()
+		.map(|inst| {
+			if let Some(xcm) = forwarded_xcm(inst) {
+				forward_hops(&xcm.0).saturating_add(1)
+			} else if let Some(xcm) = local_xcm(inst) {
+				forward_hops(&xcm.0)
+			} else {
+				0
+			}
+		})
+		.max()
+		.unwrap_or(0)
+}
+
+/// Deny executing the XCM if it would be forwarded across more than `Max` hops.
+///
+/// Programs carried by forwarding instructions (e.g. `DepositReserveAsset`) may themselves contain
+/// forwarding instructions, which can chain an arbitrary number of multi-hop transfers. Each level
+/// of such nesting counts as a hop, including nesting within error handlers and appendices.
+pub struct MaxForwardHops<Max>(PhantomData<Max>);
+impl<Max: Get<u8>> DenyExecution for MaxForwardHops<Max> {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"MaxForwardHops origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		ensure!(forward_hops(instructions) <= Max::get(), ProcessMessageError::Unsupported);
+		Ok(())
+	}
+}
+
+/// Barrier wrapper which evaluates `Inner` and rejects messages it allows with `BadFormat` if
+/// `properties.weight_credit` has not been fully consumed meanwhile.
+///
+/// This prevents messages from leaving behind unused credit, and hence free execution. `Inner`
+/// should contain the barriers which consume the credit (e.g. `TakeWeightCredit`), since the
+/// credit is only inspected once `Inner` has passed.
+pub struct DenyLeftoverCredit<Inner>(PhantomData<Inner>);
+impl<Inner: ShouldExecute> ShouldExecute for DenyLeftoverCredit<Inner> {
+	fn should_execute<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"DenyLeftoverCredit origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		Inner::should_execute(origin, instructions, max_weight, properties)?;
+		ensure!(properties.weight_credit == Weight::zero(), ProcessMessageError::BadFormat);
+		Ok(())
+	}
+
+	fn names(out: &mut Vec<&'static str>) {
+		Inner::names(out)
+	}
+
+	fn barrier_weight<RuntimeCall>(instructions: &[Instruction<RuntimeCall>]) -> Weight {
+		Inner::barrier_weight(instructions)
+	}
+}
+
+/// Deny executing the XCM if it deposits or transfers a fungible amount of an asset exceeding the
+/// cap configured for that asset by `Caps`.
+///
+/// Only `DepositAsset` and `TransferAsset` instructions with definite assets are checked, since the
+/// amounts selected by a wildcard are not known before execution.
+pub struct MaxDepositPerAsset<Caps>(PhantomData<Caps>);
+impl<Caps: Convert<AssetId, u128>> DenyExecution for MaxDepositPerAsset<Caps> {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"MaxDepositPerAsset origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		for instruction in instructions.iter() {
+			let assets = match instruction {
+				DepositAsset { assets: AssetFilter::Definite(assets), .. } |
+				TransferAsset { assets, .. } => assets,
+				_ => continue,
+			};
+			for asset in assets.inner() {
+				if let Fungible(amount) = asset.fun {
+					ensure!(
+						amount <= Caps::convert(asset.id.clone()),
+						ProcessMessageError::Unsupported
+					);
+				}
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Storage of the block at which the message queue was suspended, used by
+/// `AutoResumeSuspension`.
+pub trait SuspensionStore {
+	/// The block number type.
+	type BlockNumber: AtLeast32BitUnsigned + Copy;
+
+	/// The block at which the queue was suspended, or `None` if it was never suspended.
+	fn suspended_at() -> Option<Self::BlockNumber>;
+
+	/// The current block number.
+	fn current_block_number() -> Self::BlockNumber;
+}
+
+/// Suspends the execution of all messages for `Duration` blocks after the block stored in `Store`,
+/// automatically resuming execution afterwards.
+pub struct AutoResumeSuspension<Store, Duration>(PhantomData<(Store, Duration)>);
+impl<Store: SuspensionStore, Duration: Get<Store::BlockNumber>> CheckSuspension
+	for AutoResumeSuspension<Store, Duration>
+{
+	fn is_suspended<Call>(
+		origin: &Location,
+		instructions: &mut [Instruction<Call>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> bool {
+		log::trace!(
+			target: "xcm::barriers",
+			"AutoResumeSuspension origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		let Some(suspended_at) = Store::suspended_at() else { return false };
+		let now = Store::current_block_number();
+		now >= suspended_at && now < suspended_at.saturating_add(Duration::get())
+	}
+}
+
+/// Deny executing the XCM if its first instruction is not a recognized header, i.e. one the
+/// standard allow barriers accept a message by: an origin alteration, a fee payment, an asset
+/// withdrawal, claim or reserve/teleport deposit, a query response, a version (un)subscription or
+/// an HRMP channel notification. A message of a lone `SetTopic` is recognized as well.
+///
+/// Empty messages are left to the other barriers.
+pub struct RequireKnownHeader;
+impl DenyExecution for RequireKnownHeader {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"RequireKnownHeader origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		let Some(first) = instructions.first() else { return Ok(()) };
+		ensure!(
+			matches!(
+				first,
+				DescendOrigin(..) |
+					ClearOrigin | UniversalOrigin(..) |
+					AliasOrigin(..) | ExecuteWithOrigin { .. } |
+					BuyExecution { .. } |
+					PayFees { .. } | UnpaidExecution { .. } |
+					WithdrawAsset(..) |
+					ReserveAssetDeposited(..) |
+					ReceiveTeleportedAsset(..) |
+					ClaimAsset { .. } |
+					QueryResponse { .. } |
+					SubscribeVersion { .. } |
+					UnsubscribeVersion |
+					HrmpNewChannelOpenRequest { .. } |
+					HrmpChannelAccepted { .. } |
+					HrmpChannelClosing { .. }
+			) || matches!(instructions, [SetTopic(..)]),
+			ProcessMessageError::BadFormat
+		);
+		Ok(())
+	}
+}
+
+/// Deny executing the XCM if it contains more than `Max` identical consecutive instructions.
+///
+/// Repeating an instruction (e.g. `ClearOrigin`) is a cheap way of padding a message.
+pub struct DenyConsecutiveDuplicates<Max>(PhantomData<Max>);
+impl<Max: Get<u32>> DenyExecution for DenyConsecutiveDuplicates<Max> {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"DenyConsecutiveDuplicates origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		let (mut previous, mut run) = (None, 0u32);
+		for instruction in instructions.iter() {
+			run = if previous == Some(instruction) { run.saturating_add(1) } else { 1 };
+			ensure!(run <= Max::get(), ProcessMessageError::Unsupported);
+			previous = Some(instruction);
+		}
+		Ok(())
+	}
+}
+
+/// Deny executing the XCM if it contains a `Transact` whose `origin_kind` is not permitted for the
+/// origin by `Policy`.
+///
+/// Since `Policy` is an allow-list, `OriginKind::Superuser` is denied unless explicitly whitelisted
+/// for the origin. `Transact` instructions nested in locally executed programs (e.g. error
+/// handlers) are checked as well.
+pub struct RestrictTransactOriginKind<Policy>(PhantomData<Policy>);
+impl<Policy: ContainsPair<Location, OriginKind>> RestrictTransactOriginKind<Policy> {
+	fn check<RuntimeCall>(
+		origin: &Location,
+		instructions: &[Instruction<RuntimeCall>],
+	) -> Result<(), ProcessMessageError> {
+		for instruction in instructions {
+			if let Transact { origin_kind, .. } = instruction {
+				ensure!(Policy::contains(origin, origin_kind), ProcessMessageError::Unsupported);
+			} else if let Some(xcm) = local_xcm(instruction) {
+				Self::check(origin, &xcm.0)?;
+			}
+		}
+		Ok(())
+	}
+}
+impl<Policy: ContainsPair<Location, OriginKind>> DenyExecution
+	for RestrictTransactOriginKind<Policy>
+{
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"RestrictTransactOriginKind origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		Self::check(origin, instructions)
+	}
+}
+
+/// Deny executing the XCM if it contains a `Trap` instruction, including within locally executed
+/// nested programs (e.g. error handlers).
+///
+/// `Trap` deliberately aborts execution and can be used to probe error handling or to grief.
+pub struct DenyTrap;
+impl DenyExecution for DenyTrap {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"DenyTrap origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		ensure!(
+			!any_local_instruction(instructions, &|inst| matches!(inst, Trap(..))),
+			ProcessMessageError::BadFormat
+		);
+		Ok(())
+	}
+}
+
+/// Grants `Amount` of weight credit to origins matched by `Origins`.
+///
+/// This never authorizes execution by itself, always returning `Unsupported`, so it must be
+/// followed by `TakeWeightCredit` which does the actual authorization against the credit.
+pub struct GrantCreditTo<Origins, Amount>(PhantomData<(Origins, Amount)>);
+impl<Origins: Contains<Location>, Amount: Get<Weight>> ShouldExecute
+	for GrantCreditTo<Origins, Amount>
+{
+	fn should_execute<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"GrantCreditTo origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		if Origins::contains(origin) {
+			properties.weight_credit.saturating_accrue(Amount::get());
+		}
+		Err(ProcessMessageError::Unsupported)
+	}
+}
+
+/// Matcher of action instructions and of the proof instructions they require, used by
+/// `RequireProofFor`.
+pub trait MatchActionProof {
+	/// Returns whether `instruction` is an action requiring a proof.
+	fn is_action<Call>(instruction: &Instruction<Call>) -> bool;
+
+	/// Returns whether `instruction` is a proof permitting subsequent actions.
+	fn is_proof<Call>(instruction: &Instruction<Call>) -> bool;
+}
+
+/// Deny executing the XCM if it contains an action instruction matched by `ActionMatcher` which is
+/// not preceded by a proof instruction, e.g. a `Transact` without a prior `SetTopic` acting as a
+/// nonce.
+pub struct RequireProofFor<ActionMatcher>(PhantomData<ActionMatcher>);
+impl<ActionMatcher: MatchActionProof> DenyExecution for RequireProofFor<ActionMatcher> {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"RequireProofFor origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		let mut proven = false;
+		for instruction in instructions.iter() {
+			if ActionMatcher::is_action(instruction) {
+				ensure!(proven, ProcessMessageError::Unsupported);
+			}
+			proven |= ActionMatcher::is_proof(instruction);
+		}
+		Ok(())
+	}
+}
+
+/// Allows execution of empty messages, which have no effect, from any origin.
+///
+/// When placed first in a tuple of `ShouldExecute` barriers, empty messages are allowed before
+/// any other barrier is evaluated.
+pub struct AllowEmpty;
+impl ShouldExecute for AllowEmpty {
+	fn should_execute<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"AllowEmpty origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		ensure!(instructions.is_empty(), ProcessMessageError::Unsupported);
+		Ok(())
+	}
+}
+
+/// Deny executing empty messages.
+///
+/// When placed first in a tuple of `DenyExecution` barriers, empty messages are rejected before
+/// any other barrier is evaluated.
+pub struct DenyEmpty;
+impl DenyExecution for DenyEmpty {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"DenyEmpty origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		ensure!(!instructions.is_empty(), ProcessMessageError::BadFormat);
+		Ok(())
+	}
+}
+
+/// Deny executing the XCM if it references an asset whose id has more than `Max` interior
+/// junctions.
+///
+/// Very deep asset ids can be used to bloat storage keys.
+pub struct MaxAssetLocationDepth<Max>(PhantomData<Max>);
+impl<Max: Get<u8>> DenyExecution for MaxAssetLocationDepth<Max> {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"MaxAssetLocationDepth origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		let mut too_deep = false;
+		for instruction in instructions.iter() {
+			for_each_asset_id(instruction, &mut |id| {
+				too_deep |= id.0.interior().len() > Max::get() as usize
+			});
+		}
+		ensure!(!too_deep, ProcessMessageError::Unsupported);
+		Ok(())
+	}
+}
+
+/// Source of the weight budgeted for handling the response to a pending query, used by
+/// `CheckResponseWeight`.
+pub trait ResponseWeightBudget {
+	/// Returns the weight budgeted for handling the response to `query_id` from `origin`, or `None`
+	/// if no such query is pending.
+	fn response_weight_budget(origin: &Location, query_id: QueryId) -> Option<Weight>;
+}
+
+/// Deny executing the XCM if it contains a `QueryResponse` whose `max_weight` exceeds the weight
+/// budgeted by `Responder` for the pending query.
+///
+/// Responses to unknown queries are left to the other barriers, e.g. `AllowKnownQueryResponses`.
+pub struct CheckResponseWeight<Responder>(PhantomData<Responder>);
+impl<Responder: ResponseWeightBudget> DenyExecution for CheckResponseWeight<Responder> {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"CheckResponseWeight origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		for instruction in instructions.iter() {
+			let QueryResponse { query_id, max_weight: response_weight, .. } = instruction else {
+				continue
+			};
+			if let Some(budget) = Responder::response_weight_budget(origin, *query_id) {
+				ensure!(
+					!response_weight.any_gt(budget),
+					ProcessMessageError::Overweight(*response_weight)
+				);
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Deny executing the XCM if it contains a forwarding instruction whose destination is `Local`,
+/// i.e. the local chain itself, which would create a self-loop.
+///
+/// Forwarding instructions nested in locally executed programs (e.g. error handlers) are checked
+/// as well.
+pub struct DenySelfForward<Local>(PhantomData<Local>);
+impl<Local: Get<Location>> DenyExecution for DenySelfForward<Local> {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"DenySelfForward origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		let local = Local::get();
+		ensure!(
+			!any_local_instruction(instructions, &|inst| forward_destination(inst) == Some(&local)),
+			ProcessMessageError::Unsupported
+		);
+		Ok(())
+	}
+}
+
+/// Storage of the last sequence number seen for each origin, used by `RequireMonotonicTopic`.
+pub trait SequenceStore {
+	/// Returns the last sequence number seen for `origin`, if any.
+	fn last_sequence(origin: &Location) -> Option<XcmHash>;
+
+	/// Notes `sequence` as the last sequence number seen for `origin`.
+	fn note_sequence(origin: &Location, sequence: XcmHash);
+}
+
+/// Barrier wrapper which requires messages from origins matched by `Origins` to end with a
+/// `SetTopic` whose topic, interpreted as a big-endian sequence number, is strictly greater than
+/// the last one seen for the origin in `SeqStore`, and then evaluates `InnerBarrier`.
+///
+/// Messages without a trailing `SetTopic` are rejected with `BadFormat`, and out-of-order ones
+/// with `Unsupported`. The sequence number is only noted once `InnerBarrier` has passed, so that
+/// messages rejected by it do not consume their sequence number. Messages from other origins are
+/// left to `InnerBarrier`.
+pub struct RequireMonotonicTopic<InnerBarrier, Origins, SeqStore>(
+	PhantomData<(InnerBarrier, Origins, SeqStore)>,
+);
+impl<InnerBarrier: ShouldExecute, Origins: Contains<Location>, SeqStore: SequenceStore>
+	ShouldExecute for RequireMonotonicTopic<InnerBarrier, Origins, SeqStore>
+{
+	fn should_execute<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"RequireMonotonicTopic origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		if !Origins::contains(origin) {
+			return InnerBarrier::should_execute(origin, instructions, max_weight, properties)
+		}
+		let Some(SetTopic(sequence)) = instructions.last() else {
+			return Err(ProcessMessageError::BadFormat)
+		};
+		let sequence = *sequence;
+		// Byte arrays compare lexicographically, i.e. like the big-endian numbers they encode.
+		if let Some(last) = SeqStore::last_sequence(origin) {
+			ensure!(sequence > last, ProcessMessageError::Unsupported);
+		}
+		InnerBarrier::should_execute(origin, instructions, max_weight, properties)?;
+		SeqStore::note_sequence(origin, sequence);
+		Ok(())
+	}
+
+	fn names(out: &mut Vec<&'static str>) {
+		InnerBarrier::names(out)
+	}
+
+	fn barrier_weight<RuntimeCall>(instructions: &[Instruction<RuntimeCall>]) -> Weight {
+		InnerBarrier::barrier_weight(instructions)
+	}
+}
+
+/// Deny executing the XCM if it contains a `QueryPallet` or `ExpectPallet` instruction and comes
+/// from an origin matched by `Untrusted`.
+///
+/// Pallet introspection instructions can be abused for fingerprinting the chain. Instructions
+/// nested in locally executed programs (e.g. error handlers) are checked as well.
+pub struct DenyPalletIntrospectionFrom<Untrusted>(PhantomData<Untrusted>);
+impl<Untrusted: Contains<Location>> DenyExecution for DenyPalletIntrospectionFrom<Untrusted> {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"DenyPalletIntrospectionFrom origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		if Untrusted::contains(origin) {
+			ensure!(
+				!any_local_instruction(instructions, &|inst| {
+					matches!(inst, QueryPallet { .. } | ExpectPallet { .. })
+				}),
+				ProcessMessageError::Unsupported
+			);
+		}
+		Ok(())
+	}
+}
+
+/// The weight of `RefTime` of `ref_time` and `ProofSize` of `proof_size`.
+pub struct DimensionalWeight<RefTime, ProofSize>(PhantomData<(RefTime, ProofSize)>);
+impl<RefTime: Get<u64>, ProofSize: Get<u64>> Get<Weight> for DimensionalWeight<RefTime, ProofSize> {
+	fn get() -> Weight {
+		Weight::from_parts(RefTime::get(), ProofSize::get())
+	}
+}
+
+/// Grants `RefTime` of `ref_time` credit and `ProofSize` of `proof_size` credit to origins matched
+/// by `Origins`, each dimension saturating independently.
+///
+/// Like `GrantCreditTo`, this never authorizes execution by itself and must be followed by
+/// `TakeWeightCredit`.
+pub type GrantDimensionalCredit<Origins, RefTime, ProofSize> =
+	GrantCreditTo<Origins, DimensionalWeight<RefTime, ProofSize>>;
+
+/// Deny executing the XCM if a `BuyExecution` uses `fees` which were not loaded into the holding
+/// register by preceding `WithdrawAsset`, `ReserveAssetDeposited`, `ReceiveTeleportedAsset` or
+/// `ClaimAsset` instructions.
+///
+/// Such a `BuyExecution` would fail later anyway. The holding register is only approximated, as
+/// assets taken out of it (e.g. by `DepositAsset`) are not accounted for.
+pub struct RequireFeesInHolding;
+impl DenyExecution for RequireFeesInHolding {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"RequireFeesInHolding origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		let mut holding = Assets::new();
+		for instruction in instructions.iter() {
+			match instruction {
+				WithdrawAsset(assets) |
+				ReserveAssetDeposited(assets) |
+				ReceiveTeleportedAsset(assets) |
+				ClaimAsset { assets, .. } =>
+					assets.inner().iter().for_each(|asset| holding.push(asset.clone())),
+				BuyExecution { fees, .. } =>
+					ensure!(holding.contains(fees), ProcessMessageError::BadFormat),
+				_ => {},
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Predicate over the origin and instructions of a message, used by `When` and
+/// `RequireOriginAssertions`.
+pub trait MessagePredicate {
+	/// Returns whether the message from `origin` consisting of `instructions` matches.
+	fn matches<Call>(origin: &Location, instructions: &[Instruction<Call>]) -> bool;
+}
+
+/// Evaluates the barrier `B` only for messages matched by `Pred`, e.g. to avoid running expensive
+/// checks needlessly.
+///
+/// Messages not matched by `Pred` are rejected with `Unsupported`, leaving them to the next element
+/// of the tuple.
+pub struct When<Pred, B>(PhantomData<(Pred, B)>);
+impl<Pred: MessagePredicate, B: ShouldExecute> ShouldExecute for When<Pred, B> {
+	fn should_execute<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"When origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		ensure!(Pred::matches(origin, instructions), ProcessMessageError::Unsupported);
+		B::should_execute(origin, instructions, max_weight, properties)
+	}
+
+	fn names(out: &mut Vec<&'static str>) {
+		B::names(out)
+	}
+
+	fn barrier_weight<RuntimeCall>(instructions: &[Instruction<RuntimeCall>]) -> Weight {
+		B::barrier_weight(instructions)
+	}
+}
+
+/// Deny executing the XCM if any `SetErrorHandler` or `SetAppendix` program contains a forwarding
+/// instruction (e.g. `DepositReserveAsset`), including within nested programs.
+///
+/// Error handlers and appendices which forward assets could exfiltrate funds on failure paths.
+pub struct DenyForwardingInErrorHandler;
+impl DenyExecution for DenyForwardingInErrorHandler {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"DenyForwardingInErrorHandler origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		let forwards_on_failure = any_local_instruction(instructions, &|inst| match inst {
+			SetErrorHandler(xcm) | SetAppendix(xcm) =>
+				any_local_instruction(&xcm.0, &|inst| forwarded_xcm(inst).is_some()),
+			_ => false,
+		});
+		ensure!(!forwards_on_failure, ProcessMessageError::Unsupported);
+		Ok(())
+	}
+}
+
+/// Deny executing the XCM if it contains a `UniversalOrigin` instruction anywhere but as its first
+/// instruction, where alone it is meaningful.
+pub struct DenyLateUniversalOrigin;
+impl DenyExecution for DenyLateUniversalOrigin {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"DenyLateUniversalOrigin origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		ensure!(
+			!instructions.iter().skip(1).any(|inst| matches!(inst, UniversalOrigin(..))),
+			ProcessMessageError::BadFormat
+		);
+		Ok(())
+	}
+}
+
+/// Storage of the weight drawn from the per-block budget of `GlobalBudget`.
+pub trait BlockBudgetStore {
+	/// The block number type.
+	type BlockNumber: PartialEq;
+
+	/// The current block number.
+	fn current_block_number() -> Self::BlockNumber;
+
+	/// The block of the last draw from the budget and the weight drawn within it so far.
+	fn drawn() -> Option<(Self::BlockNumber, Weight)>;
+
+	/// Notes that `weight` has been drawn from the budget of `block` so far.
+	fn set_drawn(block: Self::BlockNumber, weight: Weight);
+}
+
+/// Allows execution as long as the `max_weight` of the message can be drawn from a budget of
+/// `PerBlock` weight shared by all messages within a block, tracked in `Store`.
+///
+/// Similar to `TakeWeightCredit`, except that the credit is global rather than per message.
+pub struct GlobalBudget<Store, PerBlock>(PhantomData<(Store, PerBlock)>);
+impl<Store: BlockBudgetStore, PerBlock: Get<Weight>> ShouldExecute
+	for GlobalBudget<Store, PerBlock>
+{
+	fn should_execute<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"GlobalBudget origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		let now = Store::current_block_number();
+		let drawn = match Store::drawn() {
+			Some((block, drawn)) if block == now => drawn,
+			_ => Weight::zero(),
+		};
+		let drawn = drawn
+			.checked_add(&max_weight)
+			.filter(|drawn| drawn.all_lte(PerBlock::get()))
+			.ok_or(ProcessMessageError::Overweight(max_weight))?;
+		Store::set_drawn(now, drawn);
+		Ok(())
+	}
+}
+
+/// Deny executing the XCM if a `DepositAsset` has a `beneficiary` which is not matched by
+/// `IsLocal`, since non-local beneficiaries are a misconfiguration of a plain deposit.
+///
+/// `DepositAsset` instructions nested in locally executed programs (e.g. error handlers) are
+/// checked as well.
+pub struct RequireLocalBeneficiary<IsLocal>(PhantomData<IsLocal>);
+impl<IsLocal: Contains<Location>> DenyExecution for RequireLocalBeneficiary<IsLocal> {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"RequireLocalBeneficiary origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		ensure!(
+			!any_local_instruction(instructions, &|inst| {
+				matches!(inst, DepositAsset { beneficiary, .. } if !IsLocal::contains(beneficiary))
+			}),
+			ProcessMessageError::Unsupported
+		);
+		Ok(())
+	}
+}
+
+/// Deny executing the XCM if it ends with a `SetTopic` of the all-zero hash.
+///
+/// Such a topic usually indicates a buggy or malicious sender defeating id-based deduplication.
+pub struct DenyZeroTopic;
+impl DenyExecution for DenyZeroTopic {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"DenyZeroTopic origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		ensure!(
+			!matches!(instructions.last(), Some(SetTopic(topic)) if *topic == [0u8; 32]),
+			ProcessMessageError::BadFormat
+		);
+		Ok(())
+	}
+}
+
+/// Barrier condition which rejects messages whose `max_weight` exceeds the weight estimated by
+/// `Weigher` more than `MaxFactor` times with `Overweight`, and then evaluates `InnerBarrier`.
+///
+/// This catches grossly over-declared weights, which would needlessly reserve block space. On
+/// rejection, the estimated weight is suggested to the sender through
+/// `properties.suggested_weight`. `Weigher` weighs the message as one of calls of type `Call`, and
+/// messages it cannot weigh are rejected with `BadFormat`.
+pub struct WeightSanity<Weigher, Call, MaxFactor, InnerBarrier>(
+	PhantomData<(Weigher, Call, MaxFactor, InnerBarrier)>,
+);
+impl<Weigher: WeightBounds<Call>, Call, MaxFactor: Get<u64>, InnerBarrier: ShouldExecute>
+	ShouldExecute for WeightSanity<Weigher, Call, MaxFactor, InnerBarrier>
+{
+	fn should_execute<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"WeightSanity origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		let mut message =
+			Xcm::<Call>(instructions.iter().cloned().map(Instruction::into).collect());
+		let estimated =
+			Weigher::weight(&mut message).map_err(|()| ProcessMessageError::BadFormat)?;
+		if !max_weight.all_lte(estimated.saturating_mul(MaxFactor::get())) {
+			properties.suggested_weight = Some(estimated);
+			return Err(ProcessMessageError::Overweight(max_weight))
+		}
+		InnerBarrier::should_execute(origin, instructions, max_weight, properties)
+	}
+
+	fn names(out: &mut Vec<&'static str>) {
+		InnerBarrier::names(out)
+	}
+
+	fn barrier_weight<RuntimeCall>(instructions: &[Instruction<RuntimeCall>]) -> Weight {
+		InnerBarrier::barrier_weight(instructions)
+	}
+}
+
+/// Deny executing the XCM if it contains a `ReceiveTeleportedAsset` of an asset which `Trusted`
+/// does not permit to be teleported from the origin.
+///
+/// Accepting teleports of untrusted assets could mint unbacked tokens.
+pub struct AllowTeleportOf<Trusted>(PhantomData<Trusted>);
+impl<Trusted: ContainsPair<Asset, Location>> DenyExecution for AllowTeleportOf<Trusted> {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"AllowTeleportOf origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		for instruction in instructions.iter() {
+			if let ReceiveTeleportedAsset(assets) = instruction {
+				ensure!(
+					assets.inner().iter().all(|asset| Trusted::contains(asset, origin)),
+					ProcessMessageError::Unsupported
+				);
+			}
+		}
+		Ok(())
+	}
+}
+
+/// A [`BarrierEvent`] sink keeping the last `N` barrier rejections in a fixed-size ring buffer,
+/// evicting the oldest rejection once full.
+///
+/// Install it with [`xcm_executor::traits::with_barrier_event_sink`].
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct RingRecorder<const N: usize> {
+	entries: [Option<(&'static str, ProcessMessageError)>; N],
+	next: usize,
+}
+
+impl<const N: usize> Default for RingRecorder<N> {
+	fn default() -> Self {
+		Self { entries: [None; N], next: 0 }
+	}
+}
+
+impl<const N: usize> RingRecorder<N> {
+	/// Creates an empty recorder.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Returns the recorded rejections as pairs of barrier type name and error, oldest first.
+	pub fn rejections(&self) -> impl Iterator<Item = (&'static str, ProcessMessageError)> + '_ {
+		let (newer, older) = self.entries.split_at(self.next);
+		older.iter().chain(newer.iter()).filter_map(|entry| *entry)
+	}
+}
+
+impl<const N: usize> BarrierEvent for RingRecorder<N> {
+	fn on_rejection(&mut self, barrier: &'static str, error: ProcessMessageError) {
+		if N == 0 {
+			return
+		}
+		self.entries[self.next] = Some((barrier, error));
+		self.next = (self.next + 1) % N;
+	}
+}
+
+/// Denies messages in which a `Transact` appears before any fee payment, i.e. `BuyExecution` or
+/// `PayFees`, with `Unsupported`.
+///
+/// Executing a `Transact` before fees are paid risks unbilled work should the fee payment later
+/// fail. Messages explicitly requesting unpaid execution through `UnpaidExecution` are left to the
+/// barrier authorizing them.
+pub struct RequireFeeBeforeTransact;
+impl DenyExecution for RequireFeeBeforeTransact {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"RequireFeeBeforeTransact origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		for instruction in instructions.iter() {
+			match instruction {
+				BuyExecution { .. } | PayFees { .. } | UnpaidExecution { .. } => break,
+				Transact { .. } => return Err(ProcessMessageError::Unsupported),
+				_ => {},
+			}
+		}
+		Ok(())
+	}
+}
+
+/// A set of barriers which must all pass, as evaluated by `All`. Implemented for tuples of
+/// `ShouldExecute` barriers.
+pub trait AllBarriers {
+	/// Evaluates every barrier in order, returning the first error encountered.
+	fn should_execute_all<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError>;
+
+	/// Appends the names of the barriers, in evaluation order.
+	fn names_all(out: &mut Vec<&'static str>);
+
+	/// Returns the total weight of evaluating every barrier against `instructions`.
+	fn barrier_weight_all<RuntimeCall>(instructions: &[Instruction<RuntimeCall>]) -> Weight;
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(1, 30)]
+#[tuple_types_custom_trait_bound(ShouldExecute)]
+impl AllBarriers for Tuple {
+	fn should_execute_all<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		for_tuples!( #( Tuple::should_execute(origin, instructions, max_weight, properties)?; )* );
+		Ok(())
+	}
+
+	fn names_all(out: &mut Vec<&'static str>) {
+		for_tuples!( #( out.push(core::any::type_name::<Tuple>()); )* );
+	}
+
+	fn barrier_weight_all<RuntimeCall>(instructions: &[Instruction<RuntimeCall>]) -> Weight {
+		let mut weight = Weight::zero();
+		for_tuples!( #( weight.saturating_accrue(Tuple::barrier_weight(instructions)); )* );
+		weight
+	}
+}
+
+/// Barrier which passes only if all of the barriers in the tuple `Barriers` pass, forwarding the
+/// first error otherwise.
+///
+/// Unlike a `DenyExecution` tuple, the sub-barriers may mutate `properties`. Their mutations are
+/// kept only if all of them pass; otherwise `properties` is restored to its prior state.
+pub struct All<Barriers>(PhantomData<Barriers>);
+impl<Barriers: AllBarriers> ShouldExecute for All<Barriers> {
+	fn should_execute<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"All origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		let prior = properties.clone();
+		Barriers::should_execute_all(origin, instructions, max_weight, properties).inspect_err(
+			|_| {
+				*properties = prior;
+			},
+		)
+	}
+
+	fn names(out: &mut Vec<&'static str>) {
+		Barriers::names_all(out)
+	}
+
+	fn barrier_weight<RuntimeCall>(instructions: &[Instruction<RuntimeCall>]) -> Weight {
+		Barriers::barrier_weight_all(instructions)
+	}
+}
+
+/// Denies messages containing more than one `BuyExecution` instruction with `BadFormat`.
+///
+/// Paying for execution more than once indicates a malformed or adversarial program.
+pub struct DenyMultipleBuyExecution;
+impl DenyExecution for DenyMultipleBuyExecution {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"DenyMultipleBuyExecution origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		let buy_executions = instructions
+			.iter()
+			.filter(|instruction| matches!(instruction, BuyExecution { .. }))
+			.count();
+		ensure!(buy_executions <= 1, ProcessMessageError::BadFormat);
+		Ok(())
+	}
+}
+
+/// Barrier which evaluates `Strict` for origins matched by `Origins`, and `Default` for all other
+/// origins.
+///
+/// Useful to apply stricter rules to specific problematic origins.
+pub struct OverrideFor<Origins, Strict, Default>(PhantomData<(Origins, Strict, Default)>);
+impl<Origins: Contains<Location>, Strict: ShouldExecute, Default: ShouldExecute> ShouldExecute
+	for OverrideFor<Origins, Strict, Default>
+{
+	fn should_execute<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"OverrideFor origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		if Origins::contains(origin) {
+			Strict::should_execute(origin, instructions, max_weight, properties)
+		} else {
+			Default::should_execute(origin, instructions, max_weight, properties)
+		}
+	}
+
+	fn names(out: &mut Vec<&'static str>) {
+		Strict::names(out);
+		Default::names(out);
+	}
+
+	fn barrier_weight<RuntimeCall>(instructions: &[Instruction<RuntimeCall>]) -> Weight {
+		Strict::barrier_weight(instructions).max(Default::barrier_weight(instructions))
+	}
+}
+
+/// Denies messages carrying a `SetHints` instruction with any hint not allowed by `Policy` with
+/// `Unsupported`, including within nested programs executed locally.
+///
+/// E.g. `Policy` might disallow `AssetClaimer` hints pointing at foreign locations.
+pub struct ValidateSetHints<Policy>(PhantomData<Policy>);
+impl<Policy: Contains<Hint>> DenyExecution for ValidateSetHints<Policy> {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"ValidateSetHints origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		ensure!(
+			!any_local_instruction(instructions, &|inst| {
+				matches!(inst, SetHints { hints } if hints.iter().any(|hint| !Policy::contains(hint)))
+			}),
+			ProcessMessageError::Unsupported
+		);
+		Ok(())
+	}
+}
+
+/// Denies messages paying for execution through `BuyExecution` with an `Unlimited` weight limit
+/// with `Unsupported`.
+///
+/// An unlimited weight limit on a paid message defeats the purpose of bounded execution.
+/// `UnpaidExecution` is unaffected.
+pub struct DenyUnlimitedPaid;
+impl DenyExecution for DenyUnlimitedPaid {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"DenyUnlimitedPaid origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		ensure!(
+			!instructions
+				.iter()
+				.any(|inst| matches!(inst, BuyExecution { weight_limit: Unlimited, .. })),
+			ProcessMessageError::Unsupported
+		);
+		Ok(())
+	}
+}
+
+/// Denies messages with a forwarding instruction whose nested program lacks a `BuyExecution`,
+/// `PayFees` or `UnpaidExecution` instruction with `Unsupported`, including within nested
+/// programs executed locally.
+///
+/// Forwarded programs without fee payment get rejected downstream, wasting the forwarding work.
+/// `InitiateTransfer` with `remote_fees` is not affected, as the executor pays the remote fees.
+pub struct RequireNestedFees;
+impl DenyExecution for RequireNestedFees {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"RequireNestedFees origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		ensure!(
+			!any_local_instruction(instructions, &|inst| {
+				if matches!(inst, InitiateTransfer { remote_fees: Some(_), .. }) {
+					return false
+				}
+				forwarded_xcm(inst).map_or(false, |xcm| {
+					!xcm.0.iter().any(|inst| {
+						matches!(
+							inst,
+							BuyExecution { .. } | PayFees { .. } | UnpaidExecution { .. }
+						)
+					})
+				})
+			}),
+			ProcessMessageError::Unsupported
+		);
+		Ok(())
+	}
+}
+
+/// Denies messages with a forwarding instruction whose nested program is encoded in more than
+/// `Limit` bytes with `Unsupported`, including within nested programs executed locally.
+///
+/// A small outer message could otherwise carry a huge nested program.
+pub struct MaxNestedSize<Limit>(PhantomData<Limit>);
+impl<Limit: Get<u32>> DenyExecution for MaxNestedSize<Limit> {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"MaxNestedSize origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		let limit = Limit::get() as usize;
+		ensure!(
+			!any_local_instruction(instructions, &|inst| {
+				forwarded_xcm(inst).map_or(false, |xcm| xcm.encoded_size() > limit)
+			}),
+			ProcessMessageError::Unsupported
+		);
+		Ok(())
+	}
+}
+
+/// Returns the total `fallback_max_weight` of the `Transact` instructions within `instructions`,
+/// including those of nested programs which are executed locally, or `None` if any of them does
+/// not declare one.
+fn transact_weight<Call>(instructions: &[Instruction<Call>]) -> Option<Weight> {
+	instructions.iter().try_fold(Weight::zero(), |total, inst| match inst {
+		Transact { fallback_max_weight, .. } =>
+			fallback_max_weight.map(|weight| total.saturating_add(weight)),
+		_ => match local_xcm(inst) {
+			Some(xcm) => transact_weight(&xcm.0).map(|weight| total.saturating_add(weight)),
+			None => Some(total),
+		},
+	})
+}
+
+/// Barrier condition which rejects messages whose `Transact` instructions together declare more
+/// weight through `fallback_max_weight` than `Remaining` with `Overweight`, and then evaluates
+/// `InnerBarrier`.
+///
+/// `Remaining` is typically the remaining dispatch budget of the block. Since the weight of a
+/// `Transact` without a `fallback_max_weight` cannot be bounded up front, messages containing one
+/// are rejected with `Unsupported`.
+pub struct TransactWithinBudget<Remaining, InnerBarrier>(PhantomData<(Remaining, InnerBarrier)>);
+impl<Remaining: Get<Weight>, InnerBarrier: ShouldExecute> ShouldExecute
+	for TransactWithinBudget<Remaining, InnerBarrier>
+{
+	fn should_execute<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"TransactWithinBudget origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		let total = transact_weight(instructions).ok_or(ProcessMessageError::Unsupported)?;
+		ensure!(total.all_lte(Remaining::get()), ProcessMessageError::Overweight(total));
+		InnerBarrier::should_execute(origin, instructions, max_weight, properties)
+	}
+
+	fn names(out: &mut Vec<&'static str>) {
+		InnerBarrier::names(out)
+	}
+
+	fn barrier_weight<RuntimeCall>(instructions: &[Instruction<RuntimeCall>]) -> Weight {
+		InnerBarrier::barrier_weight(instructions)
+	}
+}
+
+/// Model computing the risk score of a message, used by `ScoreRisk`.
+pub trait RiskModel {
+	/// Returns the risk score of a message of `instructions` from `origin`, higher values
+	/// indicating riskier messages.
+	fn score<Call>(origin: &Location, instructions: &[Instruction<Call>]) -> u8;
+}
+
+/// `RiskModel` scoring messages by their composition: `Transact` instructions add 50, wildcard
+/// deposits 30 and origins deemed foreign by `IsForeign` 20, saturating at `u8::MAX`.
+pub struct CompositionRisk<IsForeign>(PhantomData<IsForeign>);
+impl<IsForeign: Contains<Location>> RiskModel for CompositionRisk<IsForeign> {
+	fn score<Call>(origin: &Location, instructions: &[Instruction<Call>]) -> u8 {
+		let mut score = 0u8;
+		if any_local_instruction(instructions, &|inst| matches!(inst, Transact { .. })) {
+			score.saturating_accrue(50);
+		}
+		if any_local_instruction(instructions, &|inst| {
+			matches!(
+				inst,
+				DepositAsset { assets: Wild(_), .. } | DepositReserveAsset { assets: Wild(_), .. }
+			)
+		}) {
+			score.saturating_accrue(30);
+		}
+		if IsForeign::contains(origin) {
+			score.saturating_accrue(20);
+		}
+		score
+	}
+}
+
+/// Barrier which writes the risk score computed by `Model` to `properties.risk_score`, for
+/// analytics. It never allows execution by itself, always returning `Unsupported`, such that
+/// authorization is left to the barriers evaluated after it.
+pub struct ScoreRisk<Model>(PhantomData<Model>);
+impl<Model: RiskModel> ShouldExecute for ScoreRisk<Model> {
+	fn should_execute<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"ScoreRisk origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		properties.risk_score = Model::score(origin, instructions);
+		Err(ProcessMessageError::Unsupported)
+	}
+}
+
+/// Denies messages from origins matched by `Bridged` carrying a `Transact` which is not preceded by
+/// a `ClearOrigin` with `Unsupported`.
+///
+/// This prevents a bridged origin from transacting as itself.
+pub struct RequireClearOriginBeforeTransact<Bridged>(PhantomData<Bridged>);
+impl<Bridged: Contains<Location>> DenyExecution for RequireClearOriginBeforeTransact<Bridged> {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"RequireClearOriginBeforeTransact origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		if !Bridged::contains(origin) {
+			return Ok(())
+		}
+		for instruction in instructions.iter() {
+			match instruction {
+				ClearOrigin => break,
+				Transact { .. } => return Err(ProcessMessageError::Unsupported),
+				_ => {},
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Barrier which evaluates `InnerBarrier` while `Flag` is enabled, and otherwise returns
+/// `Unsupported`, leaving the decision to the barriers evaluated after it.
+///
+/// `Flag` is typically backed by runtime storage, e.g. to switch a strict mode on and off.
+pub struct FeatureGated<Flag, InnerBarrier>(PhantomData<(Flag, InnerBarrier)>);
+impl<Flag: Get<bool>, InnerBarrier: ShouldExecute> ShouldExecute
+	for FeatureGated<Flag, InnerBarrier>
+{
+	fn should_execute<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"FeatureGated origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		ensure!(Flag::get(), ProcessMessageError::Unsupported);
+		InnerBarrier::should_execute(origin, instructions, max_weight, properties)
+	}
+
+	fn names(out: &mut Vec<&'static str>) {
+		InnerBarrier::names(out)
+	}
+
+	fn barrier_weight<RuntimeCall>(instructions: &[Instruction<RuntimeCall>]) -> Weight {
+		InnerBarrier::barrier_weight(instructions)
+	}
+}
+
+/// Returns whether any instruction within `instructions`, including those of all nested
+/// programs, references a global consensus network not accepted by `known`.
+fn references_unknown_network<Call>(
+	instructions: &[Instruction<Call>],
+	known: &impl Fn(&NetworkId) -> bool,
+) -> bool {
+	instructions.iter().any(|inst| {
+		let mut unknown = match inst {
+			UniversalOrigin(GlobalConsensus(network)) | ExportMessage { network, .. } =>
+				!known(network),
+			_ => false,
+		};
+		for_each_interior(inst, &mut |interior| {
+			unknown |= interior
+				.iter()
+				.any(|junction| matches!(junction, GlobalConsensus(network) if !known(network)));
+		});
+		unknown ||
+			local_xcm(inst).map_or(false, |xcm| references_unknown_network(&xcm.0, known)) ||
+			forwarded_xcm(inst).map_or(false, |xcm| references_unknown_network(&xcm.0, known))
+	})
+}
+
+/// Denies messages referencing a global consensus network not contained in `Known` with
+/// `Unsupported`, be it through a `GlobalConsensus` junction of any location, `UniversalOrigin` or
+/// `ExportMessage`, including within all nested programs.
+///
+/// Referencing networks which the chain doesn't bridge to is always an error.
+pub struct DenyUnknownNetworks<Known>(PhantomData<Known>);
+impl<Known: Contains<NetworkId>> DenyExecution for DenyUnknownNetworks<Known> {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"DenyUnknownNetworks origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		ensure!(
+			!references_unknown_network(instructions, &|network| Known::contains(network)),
+			ProcessMessageError::Unsupported
+		);
+		Ok(())
+	}
+}
+
+/// Denies messages with a `ReportError`, `ReportHolding` or `ReportTransactStatus` whose
+/// destination is neither the origin nor allowed by `Allowed` with `Unsupported`, including within
+/// nested programs executed locally.
+///
+/// Misdirected reports could leak the contents of the holding register.
+pub struct RestrictReportDest<Allowed>(PhantomData<Allowed>);
+impl<Allowed: Contains<Location>> DenyExecution for RestrictReportDest<Allowed> {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"RestrictReportDest origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		ensure!(
+			!any_local_instruction(instructions, &|inst| match inst {
+				ReportError(response_info) |
+				ReportHolding { response_info, .. } |
+				ReportTransactStatus(response_info) => {
+					let destination = &response_info.destination;
+					destination != origin && !Allowed::contains(destination)
+				},
+				_ => false,
+			}),
+			ProcessMessageError::Unsupported
+		);
+		Ok(())
+	}
+}
+
+/// Suspends the execution of all messages while the queue depth given by `Depth`, e.g. of pending
+/// DMP or HRMP messages, exceeds `Max`.
+///
+/// This lets a chain throttle itself under backpressure.
+pub struct SuspendOnQueueDepth<Depth, Max>(PhantomData<(Depth, Max)>);
+impl<Depth: Get<u32>, Max: Get<u32>> CheckSuspension for SuspendOnQueueDepth<Depth, Max> {
+	fn is_suspended<Call>(
+		origin: &Location,
+		instructions: &mut [Instruction<Call>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> bool {
+		log::trace!(
+			target: "xcm::barriers",
+			"SuspendOnQueueDepth origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		Depth::get() > Max::get()
+	}
+}
+
+/// Denies messages which, after a `WithdrawAsset`, deposit the whole holding register through
+/// `DepositAsset` with a `All` or `AllCounted` wildcard to a beneficiary deemed foreign by
+/// `IsForeign` with `Unsupported`.
+///
+/// This pattern could drain local reserves to foreign accounts.
+pub struct DenyFullDrainToForeign<IsForeign>(PhantomData<IsForeign>);
+impl<IsForeign: Contains<Location>> DenyExecution for DenyFullDrainToForeign<IsForeign> {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"DenyFullDrainToForeign origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		let mut withdrawn = false;
+		for instruction in instructions.iter() {
+			match instruction {
+				WithdrawAsset(..) => withdrawn = true,
+				DepositAsset { assets: Wild(WildAsset::All | AllCounted(_)), beneficiary }
+					if withdrawn =>
+				{
+					ensure!(!IsForeign::contains(beneficiary), ProcessMessageError::Unsupported);
+				},
+				_ => {},
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Allows execution from origins matched by `Origins` only while `Now` is before `Deadline`,
+/// typically block numbers, and returns `Unsupported` afterwards.
+///
+/// Useful to temporarily trust origins, e.g. for staged onboarding.
+pub struct AllowUntil<Origins, Deadline, Now>(PhantomData<(Origins, Deadline, Now)>);
+impl<Origins, Deadline, Now> ShouldExecute for AllowUntil<Origins, Deadline, Now>
+where
+	Origins: Contains<Location>,
+	Deadline: TypedGet,
+	Deadline::Type: PartialOrd,
+	Now: Get<Deadline::Type>,
+{
+	fn should_execute<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"AllowUntil origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		ensure!(Origins::contains(origin), ProcessMessageError::Unsupported);
+		ensure!(Now::get() < Deadline::get(), ProcessMessageError::Unsupported);
+		Ok(())
+	}
+}
+
+/// Denies messages paying fees through `BuyExecution` or `PayFees` in an asset whose id is not
+/// contained in `Allowed` with `Unsupported`.
+///
+/// Useful to only accept a fixed set of canonical fee assets.
+pub struct RestrictFeeAsset<Allowed>(PhantomData<Allowed>);
+impl<Allowed: Contains<AssetId>> DenyExecution for RestrictFeeAsset<Allowed> {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"RestrictFeeAsset origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		for instruction in instructions.iter() {
+			if let BuyExecution { fees: asset, .. } | PayFees { asset } = instruction {
+				ensure!(Allowed::contains(&asset.id), ProcessMessageError::Unsupported);
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Matcher of action instructions, used by `DenyHereOriginActions`.
+pub trait MatchAction {
+	/// Returns whether `instruction` is an action.
+	fn is_action<Call>(instruction: &Instruction<Call>) -> bool;
+}
+
+/// Denies messages with a `Here` origin, i.e. a location without parents or junctions, containing
+/// an action instruction matched by `ActionMatcher` with `Unsupported`, including within nested
+/// programs executed locally.
+///
+/// A `Here` origin claiming such actions is suspicious, as local actions are not expected to
+/// arrive in XCM form.
+pub struct DenyHereOriginActions<ActionMatcher>(PhantomData<ActionMatcher>);
+impl<ActionMatcher: MatchAction> DenyExecution for DenyHereOriginActions<ActionMatcher> {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"DenyHereOriginActions origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		if *origin == Location::here() {
+			ensure!(
+				!any_local_instruction(instructions, &|inst| ActionMatcher::is_action(inst)),
+				ProcessMessageError::Unsupported
+			);
+		}
+		Ok(())
+	}
+}
+
+/// Extractor and verifier of detached signatures carried by messages, used by `VerifySignature`.
+pub trait SignatureVerifier {
+	/// Returns the signature bytes carried by `instruction`, if any, e.g. by a `SetTopic` or a
+	/// `Transact` of some protocol.
+	fn signature<Call>(instruction: &Instruction<Call>) -> Option<Vec<u8>>;
+
+	/// Returns whether `signature` is valid over `payload`.
+	fn verify(signature: &[u8], payload: &[u8]) -> bool;
+}
+
+/// Denies messages whose detached signature, extracted by `Verifier` from the first instruction
+/// carrying one, is not valid over the canonical encoding of the rest of the program with
+/// `Unsupported`.
+///
+/// The signed payload is the encoding of the `Xcm` made of all other instructions, in order.
+/// Messages without a signature pass, so this should be combined with other barriers where a
+/// signature is mandatory.
+pub struct VerifySignature<Verifier>(PhantomData<Verifier>);
+impl<Verifier: SignatureVerifier> DenyExecution for VerifySignature<Verifier> {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"VerifySignature origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		let Some((index, signature)) = instructions
+			.iter()
+			.enumerate()
+			.find_map(|(index, inst)| Verifier::signature(inst).map(|sig| (index, sig)))
+		else {
+			return Ok(())
+		};
+		let rest: Vec<_> = instructions
+			.iter()
+			.enumerate()
+			.filter_map(|(i, inst)| (i != index).then_some(inst))
+			.collect();
+		ensure!(Verifier::verify(&signature, &rest.encode()), ProcessMessageError::Unsupported);
+		Ok(())
+	}
+}
+
+/// Denies messages whose `max_weight` differs from the `Limited` weight limit of a `BuyExecution`
+/// or `UnpaidExecution` by more than `Tolerance`, in any dimension, with `BadFormat`.
+///
+/// Such a mismatch often signals a bug in the construction of the message. `Unlimited` weight
+/// limits are not checked.
+pub struct RequireWeightConsistency<Tolerance>(PhantomData<Tolerance>);
+impl<Tolerance: Get<Weight>> DenyExecution for RequireWeightConsistency<Tolerance> {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"RequireWeightConsistency origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		let tolerance = Tolerance::get();
+		for instruction in instructions.iter() {
+			if let BuyExecution { weight_limit: Limited(limit), .. } |
+			UnpaidExecution { weight_limit: Limited(limit), .. } = instruction
+			{
+				ensure!(
+					max_weight.saturating_sub(*limit).all_lte(tolerance) &&
+						limit.saturating_sub(max_weight).all_lte(tolerance),
+					ProcessMessageError::BadFormat
+				);
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Recorder of the verdicts of individual barriers, used by `FirstPassWins`.
+pub trait VerdictRecorder {
+	/// Records that `barrier`, identified by its type name, returned `verdict`.
+	fn record(barrier: &'static str, verdict: Result<(), ProcessMessageError>);
+}
+
+/// A set of barriers of which the first to pass wins, as evaluated by `FirstPassWins`. Implemented
+/// for tuples of `ShouldExecute` barriers.
+pub trait FirstPassBarriers {
+	/// Evaluates the barriers in order until one passes, recording each verdict with `Recorder`.
+	///
+	/// Returns the error shared by all barriers if every barrier failed with the same error, and
+	/// `Unsupported` if they failed with different errors.
+	fn should_execute_recorded<RuntimeCall, Recorder: VerdictRecorder>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError>;
+
+	/// Appends the names of the barriers, in evaluation order.
+	fn names_all(out: &mut Vec<&'static str>);
+
+	/// Returns the total weight of evaluating every barrier against `instructions`.
+	fn barrier_weight_all<RuntimeCall>(instructions: &[Instruction<RuntimeCall>]) -> Weight;
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(1, 30)]
+#[tuple_types_custom_trait_bound(ShouldExecute)]
+impl FirstPassBarriers for Tuple {
+	fn should_execute_recorded<RuntimeCall, Recorder: VerdictRecorder>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		let mut aggregated: Option<ProcessMessageError> = None;
+		for_tuples!( #(
+			let barrier = core::any::type_name::<Tuple>();
+			properties.barriers_evaluated.saturating_inc();
+			let verdict = Tuple::should_execute(origin, instructions, max_weight, properties);
+			Recorder::record(barrier, verdict);
+			match verdict {
+				Ok(()) => {
+					properties.last_rejection = None;
+					return Ok(())
+				},
+				Err(error) => {
+					properties.last_rejection = Some(barrier);
+					aggregated = match aggregated {
+						Some(previous) if previous != error => Some(ProcessMessageError::Unsupported),
+						_ => Some(error),
+					};
+				},
+			}
+		)* );
+		Err(aggregated.unwrap_or(ProcessMessageError::Unsupported))
+	}
+
+	fn names_all(out: &mut Vec<&'static str>) {
+		for_tuples!( #( out.push(core::any::type_name::<Tuple>()); )* );
+	}
+
+	fn barrier_weight_all<RuntimeCall>(instructions: &[Instruction<RuntimeCall>]) -> Weight {
+		let mut weight = Weight::zero();
+		for_tuples!( #( weight.saturating_accrue(Tuple::barrier_weight(instructions)); )* );
+		weight
+	}
+}
+
+/// Barrier which behaves like the tuple `Inner`, passing as soon as one of its barriers passes, but
+/// records the verdict of every evaluated barrier with `Recorder`.
+///
+/// If no barrier passes, the error shared by all of them is returned, or `Unsupported` if they
+/// failed with different errors. Useful for analytics, e.g. while migrating barrier configurations.
+pub struct FirstPassWins<Inner, Recorder>(PhantomData<(Inner, Recorder)>);
+impl<Inner: FirstPassBarriers, Recorder: VerdictRecorder> ShouldExecute
+	for FirstPassWins<Inner, Recorder>
+{
+	fn should_execute<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"FirstPassWins origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		Inner::should_execute_recorded::<RuntimeCall, Recorder>(
+			origin,
+			instructions,
+			max_weight,
+			properties,
+		)
+	}
+
+	fn names(out: &mut Vec<&'static str>) {
+		Inner::names_all(out)
+	}
+
+	fn barrier_weight<RuntimeCall>(instructions: &[Instruction<RuntimeCall>]) -> Weight {
+		Inner::barrier_weight_all(instructions)
+	}
+}
+
+/// Denies messages containing an `ExchangeAsset` with `maximal: true` from origins matched by
+/// `Untrusted` with `Unsupported`.
+///
+/// Maximal exchanges can move prices and should be restricted. Instructions nested in locally
+/// executed programs (e.g. error handlers) are checked as well.
+pub struct DenyMaximalExchangeFrom<Untrusted>(PhantomData<Untrusted>);
+impl<Untrusted: Contains<Location>> DenyExecution for DenyMaximalExchangeFrom<Untrusted> {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"DenyMaximalExchangeFrom origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		if Untrusted::contains(origin) {
+			ensure!(
+				!any_local_instruction(instructions, &|inst| {
+					matches!(inst, ExchangeAsset { maximal: true, .. })
+				}),
+				ProcessMessageError::Unsupported
+			);
+		}
+		Ok(())
+	}
+}
+
+/// Barrier condition which rejects messages whose `max_weight` exceeds, in any dimension, the
+/// ceiling configured for their origin by `Ceilings` with `Overweight`, and then evaluates
+/// `InnerBarrier`.
+///
+/// Origins without a configured ceiling are subject to the default returned by `Ceilings`.
+pub struct WeightCeilingPerOrigin<Ceilings, InnerBarrier>(PhantomData<(Ceilings, InnerBarrier)>);
+impl<Ceilings: Convert<Location, Weight>, InnerBarrier: ShouldExecute> ShouldExecute
+	for WeightCeilingPerOrigin<Ceilings, InnerBarrier>
+{
+	fn should_execute<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"WeightCeilingPerOrigin origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		ensure!(
+			max_weight.all_lte(Ceilings::convert(origin.clone())),
+			ProcessMessageError::Overweight(max_weight)
+		);
+		InnerBarrier::should_execute(origin, instructions, max_weight, properties)
+	}
+
+	fn names(out: &mut Vec<&'static str>) {
+		InnerBarrier::names(out)
+	}
+
+	fn barrier_weight<RuntimeCall>(instructions: &[Instruction<RuntimeCall>]) -> Weight {
+		InnerBarrier::barrier_weight(instructions)
+	}
+}
+
+/// Denies programs containing both a `ReceiveTeleportedAsset` and a `Transact` with
+/// `Unsupported`.
+///
+/// Teleporting and transacting in the same program can be used to mint and act atomically in
+/// unexpected ways. Instructions nested in locally executed programs are checked as well.
+pub struct DenyTeleportWithTransact;
+impl DenyExecution for DenyTeleportWithTransact {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"DenyTeleportWithTransact origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		let teleports =
+			any_local_instruction(instructions, &|inst| matches!(inst, ReceiveTeleportedAsset(..)));
+		let transacts =
+			any_local_instruction(instructions, &|inst| matches!(inst, Transact { .. }));
+		ensure!(!(teleports && transacts), ProcessMessageError::Unsupported);
+		Ok(())
+	}
+}
+
+/// Exempts messages from origins matched by `Exempt` from suspension, delegating the suspension
+/// check of all other messages to `Inner`.
+///
+/// Useful to keep e.g. governance messages flowing during an incident.
+pub struct ExemptFromSuspension<Exempt, Inner>(PhantomData<(Exempt, Inner)>);
+impl<Exempt: Contains<Location>, Inner: CheckSuspension> CheckSuspension
+	for ExemptFromSuspension<Exempt, Inner>
+{
+	fn is_suspended<Call>(
+		origin: &Location,
+		instructions: &mut [Instruction<Call>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> bool {
+		log::trace!(
+			target: "xcm::barriers",
+			"ExemptFromSuspension origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		!Exempt::contains(origin) &&
+			Inner::is_suspended(origin, instructions, max_weight, properties)
+	}
+}
+
+/// Runs the `DenyExecution` barrier `Inner` against every program carried by a forwarding
+/// instruction (e.g. the `remote_xcm` of an `InitiateTransfer`), recursively, denying if any of
+/// them is denied.
+///
+/// Nested programs are evaluated with the origin, `max_weight` and `properties` of the outer
+/// message; the outer program itself is not evaluated. Nesting deeper than `MaxDepth` levels is
+/// denied with `StackLimitReached`.
+pub struct CheckNestedProgram<Inner, MaxDepth>(PhantomData<(Inner, MaxDepth)>);
+impl<Inner: DenyExecution, MaxDepth: Get<u32>> CheckNestedProgram<Inner, MaxDepth> {
+	fn check_nested<Call>(
+		origin: &Location,
+		instructions: &[Instruction<Call>],
+		max_weight: Weight,
+		properties: &mut Properties,
+		depth: u32,
+	) -> Result<(), ProcessMessageError> {
+		for instruction in instructions.iter() {
+			if let Some(xcm) = forwarded_xcm(instruction) {
+				let depth = depth.saturating_add(1);
+				ensure!(depth <= MaxDepth::get(), ProcessMessageError::StackLimitReached);
+				Inner::deny_execution(origin, xcm.clone().inner_mut(), max_weight, properties)?;
+				Self::check_nested(origin, &xcm.0, max_weight, properties, depth)?;
+			} else if let Some(xcm) = local_xcm(instruction) {
+				Self::check_nested(origin, &xcm.0, max_weight, properties, depth)?;
+			}
+		}
+		Ok(())
+	}
+
+	fn nested_weight<Call>(instructions: &[Instruction<Call>]) -> Weight {
+		instructions.iter().fold(Weight::zero(), |weight, instruction| {
+			if let Some(xcm) = forwarded_xcm(instruction) {
+				weight
+					.saturating_add(Inner::barrier_weight(&xcm.0))
+					.saturating_add(Self::nested_weight(&xcm.0))
+			} else if let Some(xcm) = local_xcm(instruction) {
+				weight.saturating_add(Self::nested_weight(&xcm.0))
+			} else {
+				weight
+			}
+		})
+	}
+}
+impl<Inner: DenyExecution, MaxDepth: Get<u32>> DenyExecution
+	for CheckNestedProgram<Inner, MaxDepth>
+{
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"CheckNestedProgram origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		Self::check_nested(origin, instructions, max_weight, properties, 0)
+	}
+
+	fn barrier_weight<RuntimeCall>(instructions: &[Instruction<RuntimeCall>]) -> Weight {
+		Self::nested_weight(instructions)
+	}
+}
+
+/// Zeroes `properties.weight_credit` for messages from origins not matched by `System` before
+/// evaluating `InnerBarrier`, so that prepaid weight credit only authorizes system origins.
+///
+/// The credit of other origins is discarded rather than restored afterwards.
+pub struct CreditOnlyForSystem<System, InnerBarrier>(PhantomData<(System, InnerBarrier)>);
+impl<System: Contains<Location>, InnerBarrier: ShouldExecute> ShouldExecute
+	for CreditOnlyForSystem<System, InnerBarrier>
+{
+	fn should_execute<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"CreditOnlyForSystem origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		if !System::contains(origin) {
+			properties.weight_credit = Weight::zero();
+		}
+		InnerBarrier::should_execute(origin, instructions, max_weight, properties)
+	}
+
+	fn names(out: &mut Vec<&'static str>) {
+		InnerBarrier::names(out)
+	}
+
+	fn barrier_weight<RuntimeCall>(instructions: &[Instruction<RuntimeCall>]) -> Weight {
+		InnerBarrier::barrier_weight(instructions)
+	}
+}
+
+/// Denies messages in which a `DescendOrigin`, `ClearOrigin` or `UniversalOrigin` appears after
+/// any other instruction with `BadFormat`.
+///
+/// Origin alterations are expected to form a contiguous prefix of the program; interleaving them
+/// with effects is confusing and risky.
+pub struct DenyInterleavedOrigin;
+impl DenyExecution for DenyInterleavedOrigin {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"DenyInterleavedOrigin origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		let is_origin_alteration = |inst: &Instruction<RuntimeCall>| {
+			matches!(inst, DescendOrigin(..) | ClearOrigin | UniversalOrigin(..))
+		};
+		ensure!(
+			!instructions
+				.iter()
+				.skip_while(|inst| is_origin_alteration(inst))
+				.any(is_origin_alteration),
+			ProcessMessageError::BadFormat
+		);
+		Ok(())
+	}
+}
+
+/// Returns the opcode of `inst`, i.e. the index of its variant in its SCALE encoding, without
+/// allocating the encoding.
+fn opcode<Call>(inst: &Instruction<Call>) -> u8 {
+	struct FirstByte(Option<u8>);
+	impl codec::Output for FirstByte {
+		fn write(&mut self, bytes: &[u8]) {
+			if self.0.is_none() {
+				self.0 = bytes.first().copied();
+			}
+		}
+	}
+	let mut first = FirstByte(None);
+	inst.encode_to(&mut first);
+	first.0.unwrap_or_default()
+}
+
+/// Denies messages containing an instruction whose opcode is contained in `Disabled`, including
+/// within locally executed nested programs, with `Unsupported`.
+///
+/// A chain-wide kill-switch for specific instruction types, e.g. during an emergency; `Disabled`
+/// would typically be backed by storage. Opcodes are the indices of the `Instruction` variants in
+/// their SCALE encoding.
+pub struct InstructionKillSwitch<Disabled>(PhantomData<Disabled>);
+impl<Disabled: Contains<u8>> DenyExecution for InstructionKillSwitch<Disabled> {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"InstructionKillSwitch origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		ensure!(
+			!any_local_instruction(instructions, &|inst| Disabled::contains(&opcode(inst))),
+			ProcessMessageError::Unsupported
+		);
+		Ok(())
+	}
+}
+
+/// Deny executing the XCM if it ends with a `SetTopic` whose topic is not accepted by `Validator`.
+///
+/// Useful for protocols expecting topics of a specific structure. Messages without a trailing
+/// `SetTopic` are not checked.
+pub struct ValidateTopicFormat<Validator>(PhantomData<Validator>);
+impl<Validator: Contains<XcmHash>> DenyExecution for ValidateTopicFormat<Validator> {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"ValidateTopicFormat origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		if let Some(SetTopic(topic)) = instructions.last() {
+			ensure!(Validator::contains(topic), ProcessMessageError::BadFormat);
+		}
+		Ok(())
+	}
+}
+
+/// Storage of per-origin message counts, used by `MaxMessagesPerBlock`.
+pub trait MessageCountStore {
+	/// The block number type.
+	type BlockNumber: PartialEq;
+
+	/// The current block number.
+	fn current_block_number() -> Self::BlockNumber;
+
+	/// The block of the last message from `origin` and the number of its messages within it.
+	fn count(origin: &Location) -> Option<(Self::BlockNumber, u32)>;
+
+	/// Notes that `count` messages from `origin` passed within `block` so far.
+	fn set_count(origin: &Location, block: Self::BlockNumber, count: u32);
+}
+
+/// Barrier which evaluates `InnerBarrier` for at most `Max` messages per origin within a block,
+/// counted in `Store`, and rejects further messages from the same origin with
+/// `BarrierError::RateLimited`. The rejection is lowered to `ProcessMessageError::Yield`, so it
+/// cannot be told apart from a suspension by callers.
+///
+/// Like any barrier rejection, this fails the execution of the message with `XcmError::Barrier`,
+/// i.e. messages past the limit are dropped rather than retried in a later block. Counts reset
+/// with every block, and only messages passing `InnerBarrier` are counted.
+pub struct MaxMessagesPerBlock<InnerBarrier, Store, Max>(PhantomData<(InnerBarrier, Store, Max)>);
+impl<InnerBarrier: ShouldExecute, Store: MessageCountStore, Max: Get<u32>> ShouldExecute
+	for MaxMessagesPerBlock<InnerBarrier, Store, Max>
+{
+	fn should_execute<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"MaxMessagesPerBlock origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		let now = Store::current_block_number();
+		let count = match Store::count(origin) {
+			Some((block, count)) if block == now => count,
+			_ => 0,
+		};
+		ensure!(count < Max::get(), BarrierError::RateLimited);
+		InnerBarrier::should_execute(origin, instructions, max_weight, properties)?;
+		Store::set_count(origin, now, count.saturating_add(1));
+		Ok(())
+	}
+
+	fn names(out: &mut Vec<&'static str>) {
+		InnerBarrier::names(out)
+	}
+
+	fn barrier_weight<RuntimeCall>(instructions: &[Instruction<RuntimeCall>]) -> Weight {
+		InnerBarrier::barrier_weight(instructions)
+	}
+}
+
+/// Denies messages containing a `Transact` which is not followed by an `ExpectTransactStatus`
+/// before the next `Transact` or the end of the program with `Unsupported`.
+///
+/// This ensures that callers check the result of every `Transact`, which would otherwise be
+/// ignored.
+pub struct RequireExpectTransactStatus;
+impl DenyExecution for RequireExpectTransactStatus {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"RequireExpectTransactStatus origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		let mut unchecked = false;
+		for instruction in instructions.iter() {
+			match instruction {
+				Transact { .. } => {
+					ensure!(!unchecked, ProcessMessageError::Unsupported);
+					unchecked = true;
+				},
+				ExpectTransactStatus(..) => unchecked = false,
+				_ => {},
+			}
+		}
+		ensure!(!unchecked, ProcessMessageError::Unsupported);
+		Ok(())
+	}
+}
+
+/// Deny executing the XCM if a `DepositAsset` or `TransferAsset` has a `beneficiary` with more
+/// than `Max` interior junctions.
+///
+/// Depositing to very deep interior locations can bloat account derivation. Instructions nested in
+/// locally executed programs (e.g. error handlers) are checked as well.
+pub struct MaxBeneficiaryDepth<Max>(PhantomData<Max>);
+impl<Max: Get<u8>> DenyExecution for MaxBeneficiaryDepth<Max> {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"MaxBeneficiaryDepth origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		ensure!(
+			!any_local_instruction(instructions, &|inst| match inst {
+				DepositAsset { beneficiary, .. } | TransferAsset { beneficiary, .. } =>
+					beneficiary.interior().len() > Max::get() as usize,
+				_ => false,
+			}),
+			ProcessMessageError::Unsupported
+		);
+		Ok(())
+	}
+}
+
+/// Denies messages from origins matched by `BridgeOrigins` which do not start with a
+/// `UniversalOrigin` instruction with `Unsupported`.
+///
+/// Messages arriving over a bridge must declare the network they originate from. Messages from
+/// other origins are not checked.
+pub struct RequireUniversalOriginFrom<BridgeOrigins>(PhantomData<BridgeOrigins>);
+impl<BridgeOrigins: Contains<Location>> DenyExecution
+	for RequireUniversalOriginFrom<BridgeOrigins>
+{
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"RequireUniversalOriginFrom origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		if BridgeOrigins::contains(origin) {
+			ensure!(
+				matches!(instructions.first(), Some(UniversalOrigin(..))),
+				ProcessMessageError::Unsupported
+			);
+		}
+		Ok(())
+	}
+}
+
+/// Allows execution only of messages whose `(origin, opcode)` pair, where `opcode` is that of the
+/// first instruction, is contained in `Pairs`.
+///
+/// Expresses policies such as "this origin may only start with this kind of instruction". Empty
+/// messages are rejected with `Unsupported`. Opcodes are the indices of the `Instruction` variants
+/// in their SCALE encoding.
+pub struct AllowOriginInstruction<Pairs>(PhantomData<Pairs>);
+impl<Pairs: ContainsPair<Location, u8>> ShouldExecute for AllowOriginInstruction<Pairs> {
+	fn should_execute<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"AllowOriginInstruction origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		let first = instructions.first().ok_or(ProcessMessageError::Unsupported)?;
+		ensure!(Pairs::contains(origin, &opcode(first)), ProcessMessageError::Unsupported);
+		Ok(())
+	}
+}
+
+/// Denies messages containing a `RefundSurplus` which is not preceded by a purchase of execution
+/// through `BuyExecution` or `PayFees` with `BadFormat`.
+///
+/// Refunding without a purchase is meaningless and may mask the intent of the program.
+pub struct DenyUnbackedRefund;
+impl DenyExecution for DenyUnbackedRefund {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"DenyUnbackedRefund origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		for instruction in instructions.iter() {
+			match instruction {
+				BuyExecution { .. } | PayFees { .. } => break,
+				RefundSurplus => return Err(ProcessMessageError::BadFormat),
+				_ => {},
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Denies messages containing a `WithdrawAsset` or `ReserveAssetDeposited` whose `Assets` are not
+/// in the canonical form of XCM, i.e. sorted and deduplicated, with `BadFormat`.
+///
+/// Decoded messages are always canonical, but messages assembled in memory, e.g. by other pallets,
+/// may not be, which can cause subtle bugs in asset transactors.
+pub struct RequireCanonicalAssets;
+impl DenyExecution for RequireCanonicalAssets {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"RequireCanonicalAssets origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		for instruction in instructions.iter() {
+			if let WithdrawAsset(assets) | ReserveAssetDeposited(assets) = instruction {
+				ensure!(
+					Assets::from_sorted_and_deduplicated(assets.inner().clone()).is_ok(),
+					ProcessMessageError::BadFormat
+				);
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Pricer of fungible amounts of assets in a common unit, used by `MaxTotalValue` and
+/// `RequirePriceableFee`.
+pub trait AssetPricer {
+	/// Returns the value of `amount` of the asset `id`, or `None` if the asset has no price route.
+	fn price(id: &AssetId, amount: u128) -> Option<u128>;
+}
+
+/// Deny executing the XCM if the total value of the fungible assets it moves, as priced by
+/// `Pricer`, exceeds `Limit`, bounding the blast radius of a single message.
+///
+/// Moved assets are those loaded into the holding register by `WithdrawAsset`,
+/// `ReserveAssetDeposited`, `ReceiveTeleportedAsset` or `ClaimAsset`, and those transferred
+/// directly by `TransferAsset` or `TransferReserveAsset`. Messages moving an asset without a price
+/// route are denied as well, since their value cannot be bounded.
+pub struct MaxTotalValue<Limit, Pricer>(PhantomData<(Limit, Pricer)>);
+impl<Limit: Get<u128>, Pricer: AssetPricer> DenyExecution for MaxTotalValue<Limit, Pricer> {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"MaxTotalValue origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		let mut total: u128 = 0;
+		for instruction in instructions.iter() {
+			let assets = match instruction {
+				WithdrawAsset(assets) |
+				ReserveAssetDeposited(assets) |
+				ReceiveTeleportedAsset(assets) |
+				ClaimAsset { assets, .. } |
+				TransferAsset { assets, .. } |
+				TransferReserveAsset { assets, .. } => assets,
+				_ => continue,
+			};
+			for asset in assets.inner() {
+				if let Fungible(amount) = asset.fun {
+					let value =
+						Pricer::price(&asset.id, amount).ok_or(ProcessMessageError::Unsupported)?;
+					total = total.saturating_add(value);
+				}
+			}
+		}
+		ensure!(total <= Limit::get(), ProcessMessageError::Unsupported);
+		Ok(())
+	}
+}
+
+/// Storage of the nonces seen for each origin, used by `ExactlyOnce`.
+pub trait NonceStore {
+	/// Returns whether `nonce` was already seen for `origin`.
+	fn is_seen(origin: &Location, nonce: &XcmHash) -> bool;
+
+	/// Notes `nonce` as seen for `origin`.
+	fn note_seen(origin: &Location, nonce: XcmHash);
+}
+
+/// Barrier wrapper which requires messages to end with a `SetTopic` whose topic, acting as a nonce,
+/// was not seen before for the origin in `Store`, and then evaluates `InnerBarrier`, providing
+/// exactly-once semantics.
+///
+/// Unlike `RequireMonotonicTopic`, any unseen nonce is accepted regardless of order. Messages
+/// without a trailing `SetTopic` are rejected with `BadFormat` and replays with `Unsupported`. The
+/// nonce is only noted once `InnerBarrier` has passed, so that messages rejected by it do not
+/// consume their nonce, regardless of whether the message is eventually executed.
+pub struct ExactlyOnce<InnerBarrier, Store>(PhantomData<(InnerBarrier, Store)>);
+impl<InnerBarrier: ShouldExecute, Store: NonceStore> ShouldExecute
+	for ExactlyOnce<InnerBarrier, Store>
+{
+	fn should_execute<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"ExactlyOnce origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		let Some(SetTopic(nonce)) = instructions.last() else {
+			return Err(ProcessMessageError::BadFormat)
+		};
+		let nonce = *nonce;
+		ensure!(!Store::is_seen(origin, &nonce), ProcessMessageError::Unsupported);
+		InnerBarrier::should_execute(origin, instructions, max_weight, properties)?;
+		Store::note_seen(origin, nonce);
+		Ok(())
+	}
+
+	fn names(out: &mut Vec<&'static str>) {
+		InnerBarrier::names(out)
+	}
+
+	fn barrier_weight<RuntimeCall>(instructions: &[Instruction<RuntimeCall>]) -> Weight {
+		InnerBarrier::barrier_weight(instructions)
+	}
+}
+
+/// Denies messages paying fees through `BuyExecution` or `PayFees` in an asset which has no price
+/// route in `Router` with `Unsupported`.
+///
+/// Useful on chains where some fee assets are illiquid. Non-fungible fee assets are never
+/// priceable.
+pub struct RequirePriceableFee<Router>(PhantomData<Router>);
+impl<Router: AssetPricer> DenyExecution for RequirePriceableFee<Router> {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"RequirePriceableFee origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		for instruction in instructions.iter() {
+			if let BuyExecution { fees: asset, .. } | PayFees { asset } = instruction {
+				let priceable = match asset.fun {
+					Fungible(amount) => Router::price(&asset.id, amount).is_some(),
+					NonFungible(_) => false,
+				};
+				ensure!(priceable, ProcessMessageError::Unsupported);
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Barrier which routes `Percent` of messages to the barrier `B` and the rest to the barrier `A`,
+/// based on their `message_id`, e.g. to gradually roll out a new barrier stack.
+///
+/// A message is routed to `B` if the first eight bytes of its id, read as a little-endian integer,
+/// modulo 100 are below `Percent`, so routing is deterministic for a given id. As ids are
+/// typically chosen by the sender, e.g. through a trailing `SetTopic`, senders can choose their
+/// stack, which is acceptable only if both stacks are sound; the split is only approximate.
+/// Messages without an id are routed to `A`.
+pub struct Bucketed<A, B, Percent>(PhantomData<(A, B, Percent)>);
+impl<A: ShouldExecute, B: ShouldExecute, Percent: Get<sp_runtime::Percent>> ShouldExecute
+	for Bucketed<A, B, Percent>
+{
+	fn should_execute<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"Bucketed origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		let to_b = properties.message_id.map_or(false, |id| {
+			let mut prefix = [0u8; 8];
+			prefix.copy_from_slice(&id[..8]);
+			u64::from_le_bytes(prefix) % 100 < Percent::get().deconstruct() as u64
+		});
+		if to_b {
+			B::should_execute(origin, instructions, max_weight, properties)
+		} else {
+			A::should_execute(origin, instructions, max_weight, properties)
+		}
+	}
+
+	fn names(out: &mut Vec<&'static str>) {
+		A::names(out);
+		B::names(out);
+	}
+
+	fn barrier_weight<RuntimeCall>(instructions: &[Instruction<RuntimeCall>]) -> Weight {
+		A::barrier_weight(instructions).max(B::barrier_weight(instructions))
+	}
+}
+
+/// Deny executing the XCM if it contains a `Transact` whose call does not target a pallet index
+/// contained in `Allowed`.
+///
+/// Only the leading byte of the encoded call, i.e. the pallet index of calls of FRAME runtimes, is
+/// inspected, without decoding the call. Empty calls are denied with `BadFormat`. `Transact`
+/// instructions nested in locally executed programs (e.g. error handlers) are checked as well.
+pub struct RestrictTransactPallet<Allowed>(PhantomData<Allowed>);
+impl<Allowed: Contains<u8>> RestrictTransactPallet<Allowed> {
+	fn check<RuntimeCall>(
+		instructions: &[Instruction<RuntimeCall>],
+	) -> Result<(), ProcessMessageError> {
+		for instruction in instructions {
+			if let Transact { call, .. } = instruction {
+				let pallet_index = call.encoded().first().ok_or(ProcessMessageError::BadFormat)?;
+				ensure!(Allowed::contains(pallet_index), ProcessMessageError::Unsupported);
+			} else if let Some(xcm) = local_xcm(instruction) {
+				Self::check(&xcm.0)?;
+			}
+		}
+		Ok(())
+	}
+}
+impl<Allowed: Contains<u8>> DenyExecution for RestrictTransactPallet<Allowed> {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"RestrictTransactPallet origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		Self::check(instructions)
+	}
+}
+
+/// Deny executing the XCM if a `DepositAsset` of definite assets deposits more than available in
+/// the holding register, as tracked symbolically through the program, with `BadFormat`.
+///
+/// Such a program would fail later anyway. The holding register is loaded by `WithdrawAsset`,
+/// `ReserveAssetDeposited`, `ReceiveTeleportedAsset` and `ClaimAsset` and emptied by
+/// `DepositAsset`, where wildcards take all matching assets. Programs of `ExecuteWithOrigin` share
+/// the holding register and are tracked in place. Other instructions taking assets out of the
+/// holding register are ignored, and tracking stops at `RefundSurplus` and `ExchangeAsset`, which
+/// load amounts not known before execution, so that no valid program is denied.
+pub struct DenyOverDeposit;
+impl DenyOverDeposit {
+	/// Tracks `instructions` against `holding`, breaking once the holding register is unknown.
+	fn track<Call>(
+		instructions: &[Instruction<Call>],
+		holding: &mut AssetsInHolding,
+	) -> Result<ControlFlow<()>, ProcessMessageError> {
+		for instruction in instructions {
+			match instruction {
+				WithdrawAsset(assets) |
+				ReserveAssetDeposited(assets) |
+				ReceiveTeleportedAsset(assets) |
+				ClaimAsset { assets, .. } =>
+					assets.inner().iter().for_each(|asset| holding.subsume(asset.clone())),
+				DepositAsset { assets: assets @ Definite(_), .. } => {
+					holding.try_take(assets.clone()).map_err(|_| ProcessMessageError::BadFormat)?;
+				},
+				DepositAsset { assets, .. } => {
+					holding.saturating_take(assets.clone());
+				},
+				ExecuteWithOrigin { xcm, .. } =>
+					if Self::track(&xcm.0, holding)?.is_break() {
+						return Ok(ControlFlow::Break(()))
+					},
+				RefundSurplus | ExchangeAsset { .. } => return Ok(ControlFlow::Break(())),
+				_ => {},
+			}
+		}
+		Ok(ControlFlow::Continue(()))
+	}
+}
+impl DenyExecution for DenyOverDeposit {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"DenyOverDeposit origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		// Whether tracking stopped early does not matter, only whether it found an over-deposit.
+		Self::track(instructions, &mut AssetsInHolding::new()).map(|_| ())
+	}
+}
+
+/// Storage of the block of the last message from each origin, used by `Cooldown`.
+pub trait LastMessageStore {
+	/// The block number type.
+	type BlockNumber: AtLeast32BitUnsigned + Copy;
+
+	/// The current block number.
+	fn current_block_number() -> Self::BlockNumber;
+
+	/// The block of the last message from `origin`, or `None` if it never sent a message.
+	fn last_message(origin: &Location) -> Option<Self::BlockNumber>;
+
+	/// Notes that `origin` sent a message within `block`.
+	fn note_message(origin: &Location, block: Self::BlockNumber);
+}
+
+/// Barrier which evaluates `InnerBarrier` for messages from origins which did not send a message
+/// within the last `Blocks` blocks, as recorded in `Store`, to dampen bursts. Messages within the
+/// cooldown are rejected with `BarrierError::RateLimited`, which is lowered to
+/// `ProcessMessageError::Yield` and so cannot be told apart from a suspension by callers.
+///
+/// Like any barrier rejection, this fails the execution of the message with `XcmError::Barrier`,
+/// i.e. messages within the cooldown are dropped rather than retried in a later block. The block
+/// is recorded only for messages passing `InnerBarrier`.
+pub struct Cooldown<InnerBarrier, Store, Blocks>(PhantomData<(InnerBarrier, Store, Blocks)>);
+impl<InnerBarrier: ShouldExecute, Store: LastMessageStore, Blocks: Get<Store::BlockNumber>>
+	ShouldExecute for Cooldown<InnerBarrier, Store, Blocks>
+{
+	fn should_execute<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"Cooldown origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		let now = Store::current_block_number();
+		if let Some(last) = Store::last_message(origin) {
+			ensure!(now >= last.saturating_add(Blocks::get()), BarrierError::RateLimited);
+		}
+		InnerBarrier::should_execute(origin, instructions, max_weight, properties)?;
+		Store::note_message(origin, now);
+		Ok(())
+	}
+
+	fn names(out: &mut Vec<&'static str>) {
+		InnerBarrier::names(out)
+	}
+
+	fn barrier_weight<RuntimeCall>(instructions: &[Instruction<RuntimeCall>]) -> Weight {
+		InnerBarrier::barrier_weight(instructions)
+	}
+}
+
+/// Denies messages containing a `WithdrawAsset` or `TransferAsset` of an asset whose id is not
+/// contained in `Registry` with `Unsupported`.
+///
+/// Such assets would fail to be withdrawn by the asset transactor anyway; this catches them before
+/// execution.
+pub struct RequireRegisteredAssets<Registry>(PhantomData<Registry>);
+impl<Registry: Contains<AssetId>> DenyExecution for RequireRegisteredAssets<Registry> {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"RequireRegisteredAssets origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		for instruction in instructions.iter() {
+			if let WithdrawAsset(assets) | TransferAsset { assets, .. } = instruction {
+				ensure!(
+					assets.inner().iter().all(|asset| Registry::contains(&asset.id)),
+					ProcessMessageError::Unsupported
+				);
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Barrier condition which rejects messages whose `max_weight` overflows, in any dimension, when
+/// added to the weight already consumed as given by `Consumed`, with `Overweight`, and then
+/// evaluates `InnerBarrier`.
+///
+/// This defends against weight overflow attacks on saturating weight accounting.
+pub struct DenyWeightOverflow<Consumed, InnerBarrier>(PhantomData<(Consumed, InnerBarrier)>);
+impl<Consumed: Get<Weight>, InnerBarrier: ShouldExecute> ShouldExecute
+	for DenyWeightOverflow<Consumed, InnerBarrier>
+{
+	fn should_execute<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"DenyWeightOverflow origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		ensure!(
+			max_weight.checked_add(&Consumed::get()).is_some(),
+			ProcessMessageError::Overweight(max_weight)
+		);
+		InnerBarrier::should_execute(origin, instructions, max_weight, properties)
+	}
+
+	fn names(out: &mut Vec<&'static str>) {
+		InnerBarrier::names(out)
+	}
+
+	fn barrier_weight<RuntimeCall>(instructions: &[Instruction<RuntimeCall>]) -> Weight {
+		InnerBarrier::barrier_weight(instructions)
+	}
+}
+
+/// Denies messages of an application-specific protocol whose protocol version falls outside of
+/// `[Min, Max]`.
+///
+/// The version is the first byte of a leading `SetTopic`. Messages which do not start with
+/// `SetTopic` carry no protocol version and are not affected.
+pub struct RequireProtocolVersion<Min, Max>(PhantomData<(Min, Max)>);
+impl<Min: Get<u8>, Max: Get<u8>> DenyExecution for RequireProtocolVersion<Min, Max> {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"RequireProtocolVersion origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		if let Some(SetTopic(topic)) = instructions.first() {
+			ensure!(
+				(Min::get()..=Max::get()).contains(&topic[0]),
+				ProcessMessageError::Unsupported
+			);
+		}
+		Ok(())
+	}
+}
+
+/// Denies programs setting an appendix which contains a `Transact` with `Unsupported`.
+///
+/// The appendix is executed after the main program even when it fails, so a `Transact` there
+/// would dispatch unconditionally. Appendices set within locally executed programs are checked as
+/// well.
+pub struct DenyTransactInAppendix;
+impl DenyExecution for DenyTransactInAppendix {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"DenyTransactInAppendix origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		let transact_in_appendix = any_local_instruction(instructions, &|inst| {
+			matches!(inst, SetAppendix(appendix)
+				if any_local_instruction(&appendix.0, &|inst| matches!(inst, Transact { .. })))
+		});
+		ensure!(!transact_in_appendix, ProcessMessageError::Unsupported);
+		Ok(())
+	}
+}
+
+/// Denies messages containing an asset-moving instruction which references an asset whose id is
+/// not paired with the origin in `Allowed` with `Unsupported`.
+///
+/// Fee payments and asset expectations do not move assets and are not checked; the ids of
+/// wildcards are. Instructions nested in locally executed programs are checked against the same
+/// origin.
+pub struct RestrictAssetByOrigin<Allowed>(PhantomData<Allowed>);
+impl<Allowed: ContainsPair<Location, AssetId>> DenyExecution for RestrictAssetByOrigin<Allowed> {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"RestrictAssetByOrigin origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		let unauthorized = any_local_instruction(instructions, &|inst| {
+			if matches!(inst, BuyExecution { .. } | PayFees { .. } | ExpectAsset(..)) {
+				return false
+			}
+			let mut unauthorized = false;
+			for_each_asset_id(inst, &mut |id| unauthorized |= !Allowed::contains(origin, id));
+			unauthorized
+		});
+		ensure!(!unauthorized, ProcessMessageError::Unsupported);
+		Ok(())
+	}
+}
+
+/// Denies programs matched by `HighValue` which contain fewer than `Count` `ExpectOrigin`
+/// instructions with `Unsupported`.
+///
+/// Redundant origin assertions guard high-value flows against mistakes in origin manipulation.
+/// Only the instructions of the outer program are counted.
+pub struct RequireOriginAssertions<HighValue, Count>(PhantomData<(HighValue, Count)>);
+impl<HighValue: MessagePredicate, Count: Get<u8>> DenyExecution
+	for RequireOriginAssertions<HighValue, Count>
+{
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"RequireOriginAssertions origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		if HighValue::matches(origin, instructions) {
+			let assertions =
+				instructions.iter().filter(|inst| matches!(inst, ExpectOrigin(..))).count();
+			ensure!(assertions >= Count::get() as usize, ProcessMessageError::Unsupported);
+		}
+		Ok(())
+	}
+}
+
+/// Denies messages containing an `UnpaidExecution` whose weight limit exceeds, in any dimension,
+/// the cap configured for their origin by `Caps` with `Unsupported`.
+///
+/// An `Unlimited` weight limit is only allowed for origins whose cap is `Weight::MAX`.
+pub struct CapUnpaidWeight<Caps>(PhantomData<Caps>);
+impl<Caps: Convert<Location, Weight>> DenyExecution for CapUnpaidWeight<Caps> {
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"CapUnpaidWeight origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		for instruction in instructions.iter() {
+			if let UnpaidExecution { weight_limit, .. } = instruction {
+				let cap = Caps::convert(origin.clone());
+				let within_cap = match weight_limit {
+					Limited(weight) => weight.all_lte(cap),
+					Unlimited => cap == Weight::MAX,
+				};
+				ensure!(within_cap, ProcessMessageError::Unsupported);
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Sets the message ID to the hash by `Hasher` of the encoded instructions if the message has
+/// neither a message ID nor a `SetTopic` in the last position, so that downstream deduplication
+/// works.
+///
+/// This barrier never allows execution: it always returns `Unsupported`, leaving the decision to
+/// the next element of the tuple, and so should be placed first.
+pub struct EnsureMessageId<Hasher>(PhantomData<Hasher>);
+impl<Hasher: HashT> ShouldExecute for EnsureMessageId<Hasher>
+where
+	Hasher::Output: Into<XcmHash>,
+{
+	fn should_execute<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		log::trace!(
+			target: "xcm::barriers",
+			"EnsureMessageId origin: {:?}, instructions: {:?}, max_weight: {:?}, properties: {:?}",
+			origin, instructions, max_weight, properties,
+		);
+		if properties.message_id.is_none() && !matches!(instructions.last(), Some(SetTopic(_))) {
+			properties.message_id = Some(<Hasher as HashT>::hash(&instructions.encode()).into());
+		}
+		Err(ProcessMessageError::Unsupported)
+	}
+}
+
+/// A named layer of a barrier assembled by [`barrier_builder`](crate::barrier_builder).
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub struct BarrierLayer {
+	/// The documentation-friendly name of the layer.
+	pub name: &'static str,
+	/// The barrier type of the layer, as written in the declaration.
+	pub barrier: &'static str,
+}
+
+/// Introspection of a barrier assembled by [`barrier_builder`](crate::barrier_builder).
+pub trait BarrierLayers {
+	/// The layers of the barrier, in evaluation order.
+	const LAYERS: &'static [BarrierLayer];
+}
+
+/// Assembles a named barrier out of named layers.
+///
+/// The declared type implements `ShouldExecute` exactly like the tuple of its layers, and
+/// [`BarrierLayers`] to list the layers for introspection.
+///
+/// ```
+/// use frame_support::traits::Everything;
+/// use staging_xcm_builder::{
+/// 	barrier_builder, AllowTopLevelPaidExecutionFrom, BarrierLayers, TakeWeightCredit,
+/// };
+///
+/// barrier_builder! {
+/// 	/// The barrier of the runtime.
+/// 	pub struct Barrier {
+/// 		"local weight credit" => TakeWeightCredit,
+/// 		"paid execution" => AllowTopLevelPaidExecutionFrom<Everything>,
+/// 	}
+/// }
+///
+/// assert_eq!(Barrier::LAYERS[1].name, "paid execution");
+/// ```
+#[macro_export]
+macro_rules! barrier_builder {
+	(
+		$( #[ $attr:meta ] )*
+		$vis:vis struct $name:ident {
+			$( $layer:literal => $barrier:ty ),+ $(,)?
+		}
+	) => {
+		$( #[ $attr ] )*
+		$vis struct $name;
+
+		impl $crate::BarrierLayers for $name {
+			const LAYERS: &'static [$crate::BarrierLayer] = &[
+				$( $crate::BarrierLayer { name: $layer, barrier: stringify!($barrier) } ),+
+			];
+		}
+
+		impl $crate::__private::ShouldExecute for $name {
+			fn should_execute<RuntimeCall>(
+				origin: &$crate::__private::Location,
+				instructions: &mut [$crate::__private::Instruction<RuntimeCall>],
+				max_weight: $crate::__private::Weight,
+				properties: &mut $crate::__private::Properties,
+			) -> Result<(), $crate::__private::ProcessMessageError> {
+				<( $( $barrier, )+ ) as $crate::__private::ShouldExecute>::should_execute(
+					origin,
+					instructions,
+					max_weight,
+					properties,
+				)
+			}
+
+			fn names(out: &mut $crate::__private::Vec<&'static str>) {
+				<( $( $barrier, )+ ) as $crate::__private::ShouldExecute>::names(out)
+			}
+
+			fn barrier_weight<RuntimeCall>(
+				instructions: &[$crate::__private::Instruction<RuntimeCall>],
+			) -> $crate::__private::Weight {
+				<( $( $barrier, )+ ) as $crate::__private::ShouldExecute>::barrier_weight(
+					instructions,
+				)
+			}
+		}
+	};
+}
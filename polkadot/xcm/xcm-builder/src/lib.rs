@@ -40,12 +40,37 @@ pub use asset_exchange::SingleAssetExchangeAdapter;
 
 mod barriers;
 pub use barriers::{
-	AllowExplicitUnpaidExecutionFrom, AllowHrmpNotificationsFromRelayChain,
-	AllowKnownQueryResponses, AllowSubscriptionsFrom, AllowTopLevelPaidExecutionFrom,
-	AllowUnpaidExecutionFrom, DenyReserveTransferToRelayChain, DenyThenTry, IsChildSystemParachain,
-	IsParentsOnly, IsSiblingSystemParachain, RespectSuspension, TakeWeightCredit,
-	TrailingSetTopicAsId, WithComputedOrigin,
+	All, AllBarriers, AllowEmpty, AllowExplicitUnpaidExecutionFrom,
+	AllowHrmpNotificationsFromRelayChain, AllowKnownQueryResponses, AllowOriginInstruction,
+	AllowSubscriptionsFrom, AllowTeleportOf, AllowTopLevelPaidExecutionFrom,
+	AllowUnpaidExecutionFrom, AllowUntil, AssetPricer, AutoResumeSuspension, BarrierLayer,
+	BarrierLayers, BlockBudgetStore, Bucketed, CapUnpaidWeight, CheckNestedProgram,
+	CheckResponseWeight, CompositionRisk, Cooldown, CreditOnlyForSystem, DenyConsecutiveDuplicates,
+	DenyEmpty, DenyForwardingInErrorHandler, DenyFullDrainToForeign, DenyHereOriginActions,
+	DenyInterleavedOrigin, DenyLateUniversalOrigin, DenyLeftoverCredit, DenyMaximalExchangeFrom,
+	DenyMultipleBuyExecution, DenyOverDeposit, DenyPalletIntrospectionFrom,
+	DenyReserveTransferToRelayChain, DenySelfForward, DenyTeleportWithTransact, DenyThenTry,
+	DenyTransactInAppendix, DenyTrap, DenyUnbackedRefund, DenyUnknownNetworks, DenyUnlimitedPaid,
+	DenyWeightOverflow, DenyZeroTopic, DimensionalWeight, EnsureMessageId, ExactlyOnce,
+	ExemptFromSuspension, FeatureGated, FirstPassBarriers, FirstPassWins, GlobalBudget,
+	GrantCreditTo, GrantDimensionalCredit, InstructionKillSwitch, IsChildSystemParachain,
+	IsParentsOnly, IsSiblingSystemParachain, LastMessageStore, MatchAction, MatchActionProof,
+	MaxAssetLocationDepth, MaxBeneficiaryDepth, MaxDepositPerAsset, MaxForwardHops,
+	MaxMessagesPerBlock, MaxNestedSize, MaxTotalValue, MessageCountStore, MessagePredicate,
+	NonceStore, OverrideFor, RequireCanonicalAssets, RequireClearOriginBeforeTransact,
+	RequireExpectTransactStatus, RequireFeeBeforeTransact, RequireFeesInHolding,
+	RequireKnownHeader, RequireLocalBeneficiary, RequireMonotonicTopic, RequireNestedFees,
+	RequireOriginAssertions, RequirePriceableFee, RequireProofFor, RequireProtocolVersion,
+	RequireRegisteredAssets, RequireUniversalOriginFrom, RequireWeightConsistency,
+	RespectSuspension, ResponseWeightBudget, RestrictAssetByOrigin, RestrictDescend,
+	RestrictFeeAsset, RestrictReportDest, RestrictTransactOriginKind, RestrictTransactPallet,
+	RingRecorder, RiskModel, ScoreRisk, SequenceStore, SignatureVerifier, SuspendOnQueueDepth,
+	SuspensionStore, TakeWeightCredit, TrailingSetTopicAsId, TransactWithinBudget,
+	ValidateSetHints, ValidateTopicFormat, VerdictRecorder, VerifySignature,
+	WeightCeilingPerOrigin, WeightSanity, When, WithComputedOrigin,
 };
+#[cfg(feature = "std")]
+pub use barriers::{CatchPanics, TimeBounded, TracingShouldExecute};
 
 mod controller;
 pub use controller::{
@@ -145,3 +170,11 @@ mod weight;
 pub use weight::{
 	FixedRateOfFungible, FixedWeightBounds, TakeRevenue, UsingComponents, WeightInfoBounds,
 };
+
+#[doc(hidden)]
+pub mod __private {
+	pub use alloc::vec::Vec;
+	pub use frame_support::traits::ProcessMessageError;
+	pub use xcm::latest::{Instruction, Location, Weight};
+	pub use xcm_executor::traits::{DenyExecution, Properties, ShouldExecute};
+}
@@ -29,6 +29,11 @@ pub use xcm_executor::{
 	},
 	AssetsInHolding, Config,
 };
+#[cfg(any(test, feature = "test-utils"))]
+use {
+	frame_support::traits::ProcessMessageError,
+	xcm_executor::traits::{Properties, ShouldExecute},
+};
 
 parameter_types! {
 	pub static SubscriptionRequests: Vec<(Location, Option<(QueryId, Weight)>)> = vec![];
@@ -218,3 +223,151 @@ impl AssetLock for TestAssetLocker {
 		Ok(TestReduceTicket)
 	}
 }
+
+/// Test barrier which lets every message through.
+#[cfg(any(test, feature = "test-utils"))]
+pub struct AllowAll;
+#[cfg(any(test, feature = "test-utils"))]
+impl ShouldExecute for AllowAll {
+	fn should_execute<RuntimeCall>(
+		_origin: &Location,
+		_instructions: &mut [Instruction<RuntimeCall>],
+		_max_weight: Weight,
+		_properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		Ok(())
+	}
+}
+
+/// Test barrier which rejects every message with `Unsupported`.
+#[cfg(any(test, feature = "test-utils"))]
+pub struct DenyAll;
+#[cfg(any(test, feature = "test-utils"))]
+impl ShouldExecute for DenyAll {
+	fn should_execute<RuntimeCall>(
+		_origin: &Location,
+		_instructions: &mut [Instruction<RuntimeCall>],
+		_max_weight: Weight,
+		_properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		Err(ProcessMessageError::Unsupported)
+	}
+}
+
+/// Asserts the verdict of the `ShouldExecute` barrier `$barrier`, or of the `DenyExecution`
+/// barrier given as `deny: $barrier`, on a message of the given instructions from `$origin`,
+/// starting from empty `Properties`.
+///
+/// The expected verdict is either `Ok` or `Err(Variant)` / `Err(Variant(args..))` of
+/// `ProcessMessageError`. The instructions are of call type `()` and `max_weight` is zero unless
+/// given, e.g.:
+///
+/// ```ignore
+/// assert_barrier!(TakeWeightCredit, Parent, [ClearOrigin], max_weight: w, expects Err(Overweight(w)));
+/// assert_barrier!(Barrier, Parent, [transact()], call: RuntimeCall, expects Ok);
+/// assert_barrier!(deny: DenyTrap, Parent, [Trap(1)], expects Err(BadFormat));
+/// ```
+///
+/// Only available in tests and with the `test-utils` feature.
+#[cfg(any(test, feature = "test-utils"))]
+#[macro_export]
+macro_rules! assert_barrier {
+	(deny: $barrier:ty, $($rest:tt)+) => {
+		$crate::assert_barrier!(@check DenyExecution, deny_execution, $barrier, $($rest)+)
+	};
+	(
+		@check $trait:ident,
+		$method:ident,
+		$barrier:ty,
+		$origin:expr,
+		[$($instruction:expr),* $(,)?],
+		expects $($expected:tt)+
+	) => {
+		$crate::assert_barrier!(
+			@check $trait,
+			$method,
+			$barrier,
+			$origin,
+			[$($instruction),*],
+			call: (),
+			max_weight: $crate::__private::Weight::zero(),
+			expects $($expected)+
+		)
+	};
+	(
+		@check $trait:ident,
+		$method:ident,
+		$barrier:ty,
+		$origin:expr,
+		[$($instruction:expr),* $(,)?],
+		max_weight: $max_weight:expr,
+		expects $($expected:tt)+
+	) => {
+		$crate::assert_barrier!(
+			@check $trait,
+			$method,
+			$barrier,
+			$origin,
+			[$($instruction),*],
+			call: (),
+			max_weight: $max_weight,
+			expects $($expected)+
+		)
+	};
+	(
+		@check $trait:ident,
+		$method:ident,
+		$barrier:ty,
+		$origin:expr,
+		[$($instruction:expr),* $(,)?],
+		call: $call:ty,
+		expects $($expected:tt)+
+	) => {
+		$crate::assert_barrier!(
+			@check $trait,
+			$method,
+			$barrier,
+			$origin,
+			[$($instruction),*],
+			call: $call,
+			max_weight: $crate::__private::Weight::zero(),
+			expects $($expected)+
+		)
+	};
+	(
+		@check $trait:ident,
+		$method:ident,
+		$barrier:ty,
+		$origin:expr,
+		[$($instruction:expr),* $(,)?],
+		call: $call:ty,
+		max_weight: $max_weight:expr,
+		expects $($expected:tt)+
+	) => {{
+		let origin: $crate::__private::Location = $origin.into();
+		fn as_slice<const N: usize>(
+			instructions: &mut [$crate::__private::Instruction<$call>; N],
+		) -> &mut [$crate::__private::Instruction<$call>] {
+			instructions
+		}
+		let mut instructions = [$($instruction),*];
+		let mut properties =
+			$crate::__private::Properties::new($crate::__private::Weight::zero(), None);
+		let result = <$barrier as $crate::__private::$trait>::$method(
+			&origin,
+			as_slice(&mut instructions),
+			$max_weight,
+			&mut properties,
+		);
+		assert_eq!(result, $crate::assert_barrier!(@expected $($expected)+));
+	}};
+	(@expected Ok) => {
+		Ok(())
+	};
+	(@expected Err($error:ident $(($($arg:expr),* $(,)?))?)) => {
+		Err($crate::__private::ProcessMessageError::$error $(($($arg),*))?)
+	};
+	($barrier:ty, $($rest:tt)+) => {
+		$crate::assert_barrier!(@check ShouldExecute, should_execute, $barrier, $($rest)+)
+	};
+}
@@ -14,12 +14,16 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
-use xcm_executor::traits::Properties;
+use sp_runtime::traits::Convert;
+use xcm_executor::traits::{
+	trace_barrier_decisions, BarrierDecision, BarrierEvent, DenyExecution, Properties,
+	ShouldExecute,
+};
 
 use super::*;
 
 fn props(weight_credit: Weight) -> Properties {
-	Properties { weight_credit, message_id: None }
+	Properties::new(weight_credit, None)
 }
 
 #[test]
@@ -380,6 +384,97 @@ fn suspension_should_work() {
 	assert_eq!(r, Ok(()));
 }
 
+#[test]
+fn tracing_should_execute_records_decisions() {
+	AllowUnpaidFrom::set(vec![Parent.into()]);
+	type Inner = (TakeWeightCredit, AllowUnpaidExecutionFrom<IsInVec<AllowUnpaidFrom>>);
+	type Barrier = TracingShouldExecute<Inner>;
+	let take_weight_credit = core::any::type_name::<TakeWeightCredit>();
+	let allow_unpaid = core::any::type_name::<AllowUnpaidExecutionFrom<IsInVec<AllowUnpaidFrom>>>();
+	let inner = core::any::type_name::<Inner>();
+
+	let mut message =
+		Xcm::<()>(vec![TransferAsset { assets: (Parent, 100).into(), beneficiary: Here.into() }]);
+	let mut evaluate = |origin: Location, weight_credit| {
+		trace_barrier_decisions(|| {
+			Barrier::should_execute(
+				&origin,
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(weight_credit),
+			)
+		})
+	};
+	let (r, decisions) = evaluate(Parent.into(), Weight::zero());
+	assert_eq!(r, Ok(()));
+	assert_eq!(
+		decisions,
+		vec![
+			BarrierDecision {
+				barrier: take_weight_credit,
+				result: Err(ProcessMessageError::Overweight(Weight::from_parts(10, 10))),
+			},
+			BarrierDecision { barrier: allow_unpaid, result: Ok(()) },
+			BarrierDecision { barrier: inner, result: Ok(()) },
+		]
+	);
+
+	// the first tuple element passes, so the second one is never evaluated
+	let (r, decisions) = evaluate(Parent.into(), Weight::from_parts(10, 10));
+	assert_eq!(r, Ok(()));
+	assert_eq!(
+		decisions,
+		vec![
+			BarrierDecision { barrier: take_weight_credit, result: Ok(()) },
+			BarrierDecision { barrier: inner, result: Ok(()) },
+		]
+	);
+
+	// all elements reject
+	let (r, decisions) = evaluate(Parachain(1).into(), Weight::zero());
+	assert_eq!(r, Err(ProcessMessageError::Unsupported));
+	assert_eq!(
+		decisions,
+		vec![
+			BarrierDecision {
+				barrier: take_weight_credit,
+				result: Err(ProcessMessageError::Overweight(Weight::from_parts(10, 10))),
+			},
+			BarrierDecision {
+				barrier: allow_unpaid,
+				result: Err(ProcessMessageError::Unsupported),
+			},
+			BarrierDecision { barrier: inner, result: Err(ProcessMessageError::Unsupported) },
+		]
+	);
+
+	// barriers which are not tuples are recorded as well
+	let (r, decisions) = trace_barrier_decisions(|| {
+		TracingShouldExecute::<TakeWeightCredit>::should_execute(
+			&Parent.into(),
+			message.inner_mut(),
+			Weight::from_parts(10, 10),
+			&mut props(Weight::zero()),
+		)
+	});
+	assert_eq!(r, Err(ProcessMessageError::Overweight(Weight::from_parts(10, 10))));
+	assert_eq!(decisions, vec![BarrierDecision { barrier: take_weight_credit, result: r }]);
+	// nothing is recorded unless traced
+	assert_eq!(
+		TracingShouldExecute::<TakeWeightCredit>::should_execute(
+			&Parent.into(),
+			message.inner_mut(),
+			Weight::from_parts(10, 10),
+			&mut props(Weight::from_parts(10, 10)),
+		),
+		Ok(())
+	);
+	// the default methods are forwarded
+	let mut names = vec![];
+	Barrier::names(&mut names);
+	assert_eq!(names, vec![take_weight_credit, allow_unpaid]);
+}
+
 #[test]
 fn allow_subscriptions_from_should_work() {
 	// allow only parent
@@ -764,3 +859,4458 @@ fn deny_reserve_transfer_to_relaychain_should_work() {
 	// others instructions should pass
 	assert_deny_execution(vec![ClearOrigin], Here.into_location(), Ok(()));
 }
+
+#[test]
+fn restrict_descend_should_work() {
+	/// Only permits the sibling parachain 1000 to descend into its own accounts.
+	struct SiblingAccounts;
+	impl ContainsPair<Location, InteriorLocation> for SiblingAccounts {
+		fn contains(origin: &Location, interior: &InteriorLocation) -> bool {
+			matches!(origin.unpack(), (1, [Parachain(1000)])) &&
+				matches!(interior.as_slice(), [AccountId32 { .. }])
+		}
+	}
+
+	let sibling: Location = (Parent, Parachain(1000)).into();
+	AllowUnpaidFrom::set(vec![Parent.into(), sibling.clone()]);
+
+	let assert_should_execute = |mut xcm: Vec<Instruction<()>>, origin, expected_result| {
+		assert_eq!(
+			RestrictDescend::<SiblingAccounts, AllowUnpaidExecutionFrom<IsInVec<AllowUnpaidFrom>>>::should_execute(
+				&origin,
+				&mut xcm,
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+	let account: InteriorLocation = AccountId32 { network: None, id: [1; 32] }.into();
+
+	// no descent is simply passed to the inner barrier
+	assert_should_execute(vec![ClearOrigin], sibling.clone(), Ok(()));
+	assert_should_execute(vec![ClearOrigin], Parent.into(), Ok(()));
+	// permitted descent
+	assert_should_execute(
+		vec![DescendOrigin(account.clone()), ClearOrigin],
+		sibling.clone(),
+		Ok(()),
+	);
+	// descent into a foreign identity
+	assert_should_execute(
+		vec![DescendOrigin(Parachain(2000).into()), ClearOrigin],
+		sibling.clone(),
+		Err(ProcessMessageError::Unsupported),
+	);
+	// descent from an origin which isn't permitted to descend at all
+	assert_should_execute(
+		vec![DescendOrigin(account.clone()), ClearOrigin],
+		Parent.into(),
+		Err(ProcessMessageError::Unsupported),
+	);
+	// subsequent descents are checked against the computed origin
+	assert_should_execute(
+		vec![DescendOrigin(account.clone()), DescendOrigin(account.clone())],
+		sibling.clone(),
+		Err(ProcessMessageError::Unsupported),
+	);
+	// only leading descents are restricted
+	assert_should_execute(
+		vec![ClearOrigin, DescendOrigin(Parachain(2000).into())],
+		sibling,
+		Ok(()),
+	);
+}
+
+#[test]
+fn max_forward_hops_should_work() {
+	parameter_types! {
+		pub static MaxHops: u8 = 0;
+	}
+
+	let assert_deny_execution = |mut xcm: Vec<Instruction<()>>, max_hops, expected_result| {
+		MaxHops::set(max_hops);
+		assert_eq!(
+			MaxForwardHops::<MaxHops>::deny_execution(
+				&Here.into_location(),
+				&mut xcm,
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+	let forward = |xcm: Vec<Instruction<()>>| DepositReserveAsset {
+		assets: Wild(All),
+		dest: (Parent, Parachain(1000)).into(),
+		xcm: Xcm(xcm),
+	};
+	let one_hop = vec![forward(vec![ClearOrigin])];
+	let two_hops = vec![forward(vec![
+		ClearOrigin,
+		InitiateTeleport { assets: Wild(All), dest: Parent.into(), xcm: Xcm(vec![ClearOrigin]) },
+	])];
+
+	// zero-hop programs
+	assert_deny_execution(vec![ClearOrigin], 0, Ok(()));
+	assert_deny_execution(vec![ClearOrigin, SetAppendix(Xcm(vec![ClearOrigin]))], 0, Ok(()));
+	// at the limit
+	assert_deny_execution(one_hop.clone(), 1, Ok(()));
+	assert_deny_execution(two_hops.clone(), 2, Ok(()));
+	// over the limit
+	assert_deny_execution(one_hop.clone(), 0, Err(ProcessMessageError::Unsupported));
+	assert_deny_execution(two_hops.clone(), 1, Err(ProcessMessageError::Unsupported));
+	// forwarding from within an appendix is also counted
+	assert_deny_execution(
+		vec![SetAppendix(Xcm(two_hops))],
+		1,
+		Err(ProcessMessageError::Unsupported),
+	);
+	// the deepest branch is what matters
+	assert_deny_execution(
+		vec![forward(vec![ClearOrigin]), forward(one_hop)],
+		1,
+		Err(ProcessMessageError::Unsupported),
+	);
+}
+
+#[test]
+fn deny_leftover_credit_should_work() {
+	AllowUnpaidFrom::set(vec![Here.into()]);
+	type Barrier = DenyThenTry<
+		DenyReserveTransferToRelayChain,
+		DenyLeftoverCredit<(TakeWeightCredit, AllowUnpaidExecutionFrom<IsInVec<AllowUnpaidFrom>>)>,
+	>;
+	let max_weight = Weight::from_parts(10, 10);
+	let assert_leftover_credit = |weight_credit, expected_result| {
+		let mut message = Xcm::<()>(vec![ClearOrigin]);
+		assert_eq!(
+			Barrier::should_execute(
+				&Here.into(),
+				message.inner_mut(),
+				max_weight,
+				&mut props(weight_credit),
+			),
+			expected_result
+		);
+	};
+
+	// no credit at all
+	assert_leftover_credit(Weight::zero(), Ok(()));
+	// credit fully consumed
+	assert_leftover_credit(Weight::from_parts(10, 10), Ok(()));
+	// credit partially consumed
+	assert_leftover_credit(Weight::from_parts(20, 10), Err(ProcessMessageError::BadFormat));
+	// credit insufficient, hence not consumed at all
+	assert_leftover_credit(Weight::from_parts(5, 5), Err(ProcessMessageError::BadFormat));
+	// messages rejected by the inner barrier are rejected as before
+	AllowUnpaidFrom::set(vec![]);
+	assert_leftover_credit(Weight::zero(), Err(ProcessMessageError::Unsupported));
+}
+
+#[test]
+fn deny_leftover_credit_through_executor() {
+	type Config = BarrierTestConfig<DenyLeftoverCredit<TestBarrier>>;
+	let execute = |weight_credit| {
+		let message = Xcm::<TestCall>(vec![ClearOrigin]);
+		let mut hash = fake_message_hash(&message);
+		XcmExecutor::<Config>::prepare_and_execute(
+			Here,
+			message,
+			&mut hash,
+			Weight::from_parts(10, 10),
+			weight_credit,
+		)
+	};
+
+	// local execution paying exactly for the message, as e.g. `pallet-xcm` does
+	assert_eq!(
+		execute(Weight::from_parts(10, 10)),
+		Outcome::Complete { used: Weight::from_parts(10, 10) }
+	);
+	// credit left over
+	assert_eq!(execute(Weight::from_parts(20, 20)), Outcome::Error { error: XcmError::Barrier });
+}
+
+#[test]
+fn max_deposit_per_asset_should_work() {
+	struct TestCaps;
+	impl Convert<AssetId, u128> for TestCaps {
+		fn convert(id: AssetId) -> u128 {
+			match id {
+				AssetId(Location { parents: 0, interior: Here }) => 100,
+				_ => u128::MAX,
+			}
+		}
+	}
+
+	let beneficiary: Location = AccountIndex64 { network: None, index: 1 }.into();
+	let assert_deposit = |message: Xcm<()>, expected_result| {
+		let mut message = message;
+		assert_eq!(
+			MaxDepositPerAsset::<TestCaps>::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+
+	// under the cap
+	assert_deposit(
+		Xcm(vec![
+			DepositAsset { assets: Definite((Here, 100).into()), beneficiary: beneficiary.clone() },
+			TransferAsset { assets: (Here, 50).into(), beneficiary: beneficiary.clone() },
+		]),
+		Ok(()),
+	);
+	// over the cap
+	assert_deposit(
+		Xcm(vec![DepositAsset {
+			assets: Definite((Here, 101).into()),
+			beneficiary: beneficiary.clone(),
+		}]),
+		Err(ProcessMessageError::Unsupported),
+	);
+	assert_deposit(
+		Xcm(vec![TransferAsset { assets: (Here, 101).into(), beneficiary: beneficiary.clone() }]),
+		Err(ProcessMessageError::Unsupported),
+	);
+	// asset without a configured cap falls back to the default
+	assert_deposit(
+		Xcm(vec![DepositAsset {
+			assets: Definite((Parent, u128::MAX).into()),
+			beneficiary: beneficiary.clone(),
+		}]),
+		Ok(()),
+	);
+	// wildcards are not checked
+	assert_deposit(Xcm(vec![DepositAsset { assets: All.into(), beneficiary }]), Ok(()));
+}
+
+#[test]
+fn auto_resume_suspension_should_work() {
+	parameter_types! {
+		pub static SuspendedAt: Option<u64> = None;
+		pub static CurrentBlock: u64 = 0;
+		pub const SuspensionDuration: u64 = 10;
+	}
+	struct TestStore;
+	impl SuspensionStore for TestStore {
+		type BlockNumber = u64;
+		fn suspended_at() -> Option<u64> {
+			SuspendedAt::get()
+		}
+		fn current_block_number() -> u64 {
+			CurrentBlock::get()
+		}
+	}
+
+	let is_suspended = || {
+		AutoResumeSuspension::<TestStore, SuspensionDuration>::is_suspended(
+			&Parent.into(),
+			Xcm::<()>(vec![ClearOrigin]).inner_mut(),
+			Weight::from_parts(10, 10),
+			&mut props(Weight::zero()),
+		)
+	};
+
+	// never suspended
+	CurrentBlock::set(100);
+	assert!(!is_suspended());
+
+	// suspended at block 100, resumes at block 110
+	SuspendedAt::set(Some(100));
+	assert!(is_suspended());
+	CurrentBlock::set(109);
+	assert!(is_suspended());
+	CurrentBlock::set(110);
+	assert!(!is_suspended());
+	CurrentBlock::set(1_000);
+	assert!(!is_suspended());
+}
+
+#[test]
+fn require_known_header_should_work() {
+	let assert_header = |instruction: Instruction<()>, expected_result| {
+		let mut message = Xcm::<()>(vec![instruction, ClearOrigin]);
+		assert_eq!(
+			RequireKnownHeader::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+
+	let assets: Assets = (Parent, 100).into();
+	for header in [
+		DescendOrigin(Parachain(1).into()),
+		ClearOrigin,
+		UniversalOrigin(GlobalConsensus(Kusama)),
+		AliasOrigin(Parent.into()),
+		ExecuteWithOrigin { descendant_origin: None, xcm: Xcm(vec![]) },
+		BuyExecution { fees: (Parent, 100).into(), weight_limit: Unlimited },
+		PayFees { asset: (Parent, 100).into() },
+		UnpaidExecution { weight_limit: Unlimited, check_origin: None },
+		WithdrawAsset(assets.clone()),
+		ReserveAssetDeposited(assets.clone()),
+		ReceiveTeleportedAsset(assets.clone()),
+		ClaimAsset { assets: assets.clone(), ticket: Here.into() },
+		QueryResponse {
+			query_id: 42,
+			response: Response::Null,
+			max_weight: Weight::zero(),
+			querier: None,
+		},
+		SubscribeVersion { query_id: 42, max_response_weight: Weight::zero() },
+		UnsubscribeVersion,
+		HrmpNewChannelOpenRequest { sender: 1, max_message_size: 100, max_capacity: 10 },
+		HrmpChannelAccepted { recipient: 1 },
+		HrmpChannelClosing { initiator: 1, sender: 1, recipient: 2 },
+	] {
+		assert_header(header, Ok(()));
+	}
+
+	assert_header(
+		TransferAsset { assets, beneficiary: Here.into() },
+		Err(ProcessMessageError::BadFormat),
+	);
+	assert_header(RefundSurplus, Err(ProcessMessageError::BadFormat));
+	// a topic is only recognized on its own
+	assert_barrier!(deny: RequireKnownHeader, Parent, [SetTopic([1; 32])], expects Ok);
+	assert_header(SetTopic([1; 32]), Err(ProcessMessageError::BadFormat));
+}
+
+#[test]
+fn barrier_builder_should_work() {
+	barrier_builder! {
+		/// A barrier built out of named layers.
+		pub struct BuiltBarrier {
+			"local weight credit" => TakeWeightCredit,
+			"paid execution" => AllowTopLevelPaidExecutionFrom<IsInVec<AllowPaidFrom>>,
+			"unpaid execution" => AllowUnpaidExecutionFrom<IsInVec<AllowUnpaidFrom>>,
+		}
+	}
+	type HandWrittenBarrier = (
+		TakeWeightCredit,
+		AllowTopLevelPaidExecutionFrom<IsInVec<AllowPaidFrom>>,
+		AllowUnpaidExecutionFrom<IsInVec<AllowUnpaidFrom>>,
+	);
+
+	assert_eq!(
+		BuiltBarrier::LAYERS.iter().map(|layer| layer.name).collect::<Vec<_>>(),
+		vec!["local weight credit", "paid execution", "unpaid execution"],
+	);
+	assert_eq!(BuiltBarrier::LAYERS[0].barrier, "TakeWeightCredit");
+
+	AllowPaidFrom::set(vec![Parent.into()]);
+	AllowUnpaidFrom::set(vec![Parachain(1).into()]);
+
+	let paid_message = Xcm::<()>(vec![
+		ReserveAssetDeposited((Parent, 100).into()),
+		BuyExecution {
+			fees: (Parent, 100).into(),
+			weight_limit: Limited(Weight::from_parts(30, 30)),
+		},
+		DepositAsset { assets: AllCounted(1).into(), beneficiary: Here.into() },
+	]);
+	for (origin, message, weight_credit) in [
+		(Location::parent(), paid_message.clone(), Weight::zero()),
+		(Parachain(1).into(), paid_message.clone(), Weight::zero()),
+		(Parachain(2).into(), paid_message.clone(), Weight::zero()),
+		(Parachain(2).into(), paid_message, Weight::from_parts(10, 10)),
+		(Parachain(2).into(), Xcm::<()>(vec![ClearOrigin]), Weight::zero()),
+	] {
+		let (mut built_message, mut hand_written_message) = (message.clone(), message);
+		let (mut built_properties, mut hand_written_properties) =
+			(props(weight_credit), props(weight_credit));
+		assert_eq!(
+			BuiltBarrier::should_execute(
+				&origin,
+				built_message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut built_properties,
+			),
+			HandWrittenBarrier::should_execute(
+				&origin,
+				hand_written_message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut hand_written_properties,
+			),
+		);
+		assert_eq!(built_properties, hand_written_properties);
+	}
+}
+
+#[test]
+fn deny_consecutive_duplicates_should_work() {
+	parameter_types! {
+		pub const MaxDuplicates: u32 = 2;
+	}
+	let assert_duplicates = |message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			DenyConsecutiveDuplicates::<MaxDuplicates>::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+
+	// within the limit
+	assert_duplicates(vec![], Ok(()));
+	assert_duplicates(vec![ClearOrigin, ClearOrigin], Ok(()));
+	assert_duplicates(
+		vec![ClearOrigin, ClearOrigin, RefundSurplus, ClearOrigin, ClearOrigin],
+		Ok(()),
+	);
+	// instructions of the same kind with different operands are not identical
+	assert_duplicates(
+		vec![
+			DescendOrigin(Parachain(1).into()),
+			DescendOrigin(Parachain(2).into()),
+			DescendOrigin(Parachain(3).into()),
+		],
+		Ok(()),
+	);
+	// over the limit
+	assert_duplicates(
+		vec![RefundSurplus, ClearOrigin, ClearOrigin, ClearOrigin],
+		Err(ProcessMessageError::Unsupported),
+	);
+}
+
+#[test]
+fn restrict_transact_origin_kind_should_work() {
+	// The relay chain may use any origin kind, other origins anything but `Superuser`.
+	struct TestPolicy;
+	impl ContainsPair<Location, OriginKind> for TestPolicy {
+		fn contains(origin: &Location, origin_kind: &OriginKind) -> bool {
+			*origin == Location::parent() || *origin_kind != OriginKind::Superuser
+		}
+	}
+
+	let assert_transact = |origin: Location, origin_kind, expected_result| {
+		let mut message = Xcm::<()>(vec![Transact {
+			origin_kind,
+			fallback_max_weight: None,
+			call: vec![].into(),
+		}]);
+		assert_eq!(
+			RestrictTransactOriginKind::<TestPolicy>::deny_execution(
+				&origin,
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+
+	let trusted = Location::parent();
+	let untrusted = Location::new(1, [Parachain(1000)]);
+	for origin_kind in
+		[OriginKind::Native, OriginKind::SovereignAccount, OriginKind::Superuser, OriginKind::Xcm]
+	{
+		assert_transact(trusted.clone(), origin_kind, Ok(()));
+	}
+	for origin_kind in [OriginKind::Native, OriginKind::SovereignAccount, OriginKind::Xcm] {
+		assert_transact(untrusted.clone(), origin_kind, Ok(()));
+	}
+	assert_transact(
+		untrusted.clone(),
+		OriginKind::Superuser,
+		Err(ProcessMessageError::Unsupported),
+	);
+
+	// nested in an error handler
+	let mut message = Xcm::<()>(vec![SetErrorHandler(Xcm(vec![Transact {
+		origin_kind: OriginKind::Superuser,
+		fallback_max_weight: None,
+		call: vec![].into(),
+	}]))]);
+	assert_eq!(
+		RestrictTransactOriginKind::<TestPolicy>::deny_execution(
+			&untrusted,
+			message.inner_mut(),
+			Weight::from_parts(10, 10),
+			&mut props(Weight::zero()),
+		),
+		Err(ProcessMessageError::Unsupported)
+	);
+}
+
+#[test]
+fn should_execute_names_should_work() {
+	type Barrier = (
+		TakeWeightCredit,
+		AllowTopLevelPaidExecutionFrom<IsInVec<AllowPaidFrom>>,
+		AllowUnpaidExecutionFrom<IsInVec<AllowUnpaidFrom>>,
+	);
+
+	let mut names = vec![];
+	Barrier::names(&mut names);
+	assert_eq!(
+		names,
+		vec![
+			core::any::type_name::<TakeWeightCredit>(),
+			core::any::type_name::<AllowTopLevelPaidExecutionFrom<IsInVec<AllowPaidFrom>>>(),
+			core::any::type_name::<AllowUnpaidExecutionFrom<IsInVec<AllowUnpaidFrom>>>(),
+		]
+	);
+	assert!(names[0].ends_with("TakeWeightCredit"));
+
+	// a single barrier names itself
+	let mut names = vec![];
+	TakeWeightCredit::names(&mut names);
+	assert_eq!(names, vec![core::any::type_name::<TakeWeightCredit>()]);
+
+	// wrappers name the barriers they wrap
+	use frame_support::traits::{ConstBool, Everything};
+	parameter_types! {
+		pub UniversalLocation: InteriorLocation = [GlobalConsensus(Polkadot), Parachain(1000)].into();
+	}
+	let names_of = |names: fn(&mut Vec<&'static str>)| {
+		let mut out = vec![];
+		names(&mut out);
+		out
+	};
+	let inner = vec![core::any::type_name::<TakeWeightCredit>()];
+	assert_eq!(
+		names_of(
+			TrailingSetTopicAsId::<
+				DenyThenTry<
+					DenyTrap,
+					WithComputedOrigin<TakeWeightCredit, UniversalLocation, ConstU32<8>>,
+				>,
+			>::names
+		),
+		inner
+	);
+	assert_eq!(names_of(FeatureGated::<ConstBool<true>, TakeWeightCredit>::names), inner);
+	assert_eq!(names_of(CreditOnlyForSystem::<Everything, TakeWeightCredit>::names), inner);
+	assert_eq!(names_of(RespectSuspension::<TakeWeightCredit, ()>::names), inner);
+	assert_eq!(
+		names_of(OverrideFor::<Everything, TakeWeightCredit, AllowAll>::names),
+		vec![core::any::type_name::<TakeWeightCredit>(), core::any::type_name::<AllowAll>()]
+	);
+	assert_eq!(
+		names_of(crate::All::<(TakeWeightCredit, AllowAll)>::names),
+		vec![core::any::type_name::<TakeWeightCredit>(), core::any::type_name::<AllowAll>()]
+	);
+}
+
+#[test]
+fn deny_trap_should_work() {
+	let assert_trap = |message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			DenyTrap::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+
+	assert_trap(vec![ClearOrigin, RefundSurplus], Ok(()));
+	assert_trap(vec![ClearOrigin, Trap(1)], Err(ProcessMessageError::BadFormat));
+	assert_trap(
+		vec![SetErrorHandler(Xcm(vec![Trap(2)])), ClearOrigin],
+		Err(ProcessMessageError::BadFormat),
+	);
+}
+
+#[test]
+fn grant_credit_to_should_work() {
+	parameter_types! {
+		pub TrustedOrigins: Vec<Location> = vec![Parent.into()];
+		pub const GrantedCredit: Weight = Weight::from_parts(10, 10);
+	}
+	type Barrier = (GrantCreditTo<IsInVec<TrustedOrigins>, GrantedCredit>, TakeWeightCredit);
+
+	let assert_grant = |origin: Location, expected_result, expected_credit| {
+		let mut message = Xcm::<()>(vec![ClearOrigin]);
+		let mut properties = props(Weight::from_parts(5, 5));
+		assert_eq!(
+			Barrier::should_execute(
+				&origin,
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut properties,
+			),
+			expected_result
+		);
+		assert_eq!(properties.weight_credit, expected_credit);
+	};
+
+	// credit is added for the trusted origin and taken by `TakeWeightCredit`
+	assert_grant(Parent.into(), Ok(()), Weight::from_parts(5, 5));
+	// but not for others
+	assert_grant(
+		Parachain(1).into(),
+		Err(ProcessMessageError::Unsupported),
+		Weight::from_parts(5, 5),
+	);
+
+	// granting alone never authorizes execution
+	let mut properties = props(Weight::zero());
+	assert_eq!(
+		GrantCreditTo::<IsInVec<TrustedOrigins>, GrantedCredit>::should_execute(
+			&Parent.into(),
+			Xcm::<()>(vec![ClearOrigin]).inner_mut(),
+			Weight::from_parts(10, 10),
+			&mut properties,
+		),
+		Err(ProcessMessageError::Unsupported)
+	);
+	assert_eq!(properties.weight_credit, Weight::from_parts(10, 10));
+}
+
+#[test]
+fn require_proof_for_should_work() {
+	struct TransactWithTopic;
+	impl MatchActionProof for TransactWithTopic {
+		fn is_action<Call>(instruction: &Instruction<Call>) -> bool {
+			matches!(instruction, Transact { .. })
+		}
+		fn is_proof<Call>(instruction: &Instruction<Call>) -> bool {
+			matches!(instruction, SetTopic(..) | ExpectTransactStatus(..))
+		}
+	}
+
+	let transact = || Transact {
+		origin_kind: OriginKind::Native,
+		fallback_max_weight: None,
+		call: vec![].into(),
+	};
+	let assert_proof = |message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			RequireProofFor::<TransactWithTopic>::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+
+	// no action, no proof needed
+	assert_proof(vec![ClearOrigin], Ok(()));
+	// with the required proof
+	assert_proof(vec![SetTopic([1; 32]), transact()], Ok(()));
+	assert_proof(vec![ExpectTransactStatus(MaybeErrorCode::Success), transact()], Ok(()));
+	// without the required proof
+	assert_proof(vec![transact()], Err(ProcessMessageError::Unsupported));
+	// the proof must precede the action
+	assert_proof(vec![transact(), SetTopic([1; 32])], Err(ProcessMessageError::Unsupported));
+}
+
+#[test]
+fn allow_and_deny_empty_should_work() {
+	let mut empty = Xcm::<()>(vec![]);
+	let mut non_empty = Xcm::<()>(vec![ClearOrigin]);
+
+	assert_eq!(
+		AllowEmpty::should_execute(
+			&Parent.into(),
+			empty.inner_mut(),
+			Weight::zero(),
+			&mut props(Weight::zero()),
+		),
+		Ok(())
+	);
+	assert_eq!(
+		AllowEmpty::should_execute(
+			&Parent.into(),
+			non_empty.inner_mut(),
+			Weight::from_parts(10, 10),
+			&mut props(Weight::zero()),
+		),
+		Err(ProcessMessageError::Unsupported)
+	);
+
+	assert_eq!(
+		DenyEmpty::deny_execution(
+			&Parent.into(),
+			empty.inner_mut(),
+			Weight::zero(),
+			&mut props(Weight::zero()),
+		),
+		Err(ProcessMessageError::BadFormat)
+	);
+	assert_eq!(
+		DenyEmpty::deny_execution(
+			&Parent.into(),
+			non_empty.inner_mut(),
+			Weight::from_parts(10, 10),
+			&mut props(Weight::zero()),
+		),
+		Ok(())
+	);
+}
+
+#[test]
+fn empty_messages_short_circuit_tuples() {
+	// placed first, `AllowEmpty` and `DenyEmpty` spare the evaluation of any other barrier
+	let mut properties = props(Weight::zero());
+	assert_eq!(
+		<(AllowEmpty, DenyAll, DenyAll)>::should_execute(
+			&Parent.into(),
+			Xcm::<()>(vec![]).inner_mut(),
+			Weight::zero(),
+			&mut properties,
+		),
+		Ok(())
+	);
+	assert_eq!(properties.barriers_evaluated, 1);
+
+	let (result, decisions) = trace_barrier_decisions(|| {
+		<(DenyEmpty, DenyTrap, DenyReserveTransferToRelayChain)>::deny_execution(
+			&Parent.into(),
+			Xcm::<()>(vec![]).inner_mut(),
+			Weight::zero(),
+			&mut props(Weight::zero()),
+		)
+	});
+	assert_eq!(result, Err(ProcessMessageError::BadFormat));
+	assert_eq!(
+		decisions,
+		vec![BarrierDecision { barrier: core::any::type_name::<DenyEmpty>(), result }]
+	);
+}
+
+#[test]
+fn max_asset_location_depth_should_work() {
+	parameter_types! {
+		pub const MaxDepth: u8 = 2;
+	}
+	let assert_depth = |message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			MaxAssetLocationDepth::<MaxDepth>::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+
+	let shallow = Location::new(1, [Parachain(1000), PalletInstance(50)]);
+	let deep = Location::new(1, [Parachain(1000), PalletInstance(50), GeneralIndex(1)]);
+
+	// shallow asset ids
+	assert_depth(
+		vec![
+			WithdrawAsset((Parent, 100).into()),
+			BuyExecution { fees: (shallow.clone(), 100).into(), weight_limit: Unlimited },
+			DepositAsset {
+				assets: AllOf { id: AssetId(shallow), fun: WildFungible }.into(),
+				beneficiary: Here.into(),
+			},
+		],
+		Ok(()),
+	);
+	// deep asset ids
+	assert_depth(
+		vec![WithdrawAsset((deep.clone(), 100).into())],
+		Err(ProcessMessageError::Unsupported),
+	);
+	assert_depth(
+		vec![
+			WithdrawAsset((Parent, 100).into()),
+			DepositAsset {
+				assets: AllOf { id: AssetId(deep), fun: WildFungible }.into(),
+				beneficiary: Here.into(),
+			},
+		],
+		Err(ProcessMessageError::Unsupported),
+	);
+}
+
+#[test]
+fn derived_barriers_should_work() {
+	parameter_types! {
+		pub const MaxHops: u8 = 1;
+	}
+
+	#[derive(DenyExecution)]
+	#[allow(dead_code)]
+	struct DerivedDenyPolicy<Max> {
+		trap: DenyTrap,
+		empty: DenyEmpty,
+		hops: MaxForwardHops<Max>,
+		#[barrier(skip)]
+		marker: core::marker::PhantomData<Max>,
+	}
+	type HandWrittenDenyPolicy = (DenyTrap, DenyEmpty, MaxForwardHops<MaxHops>);
+
+	#[derive(ShouldExecute)]
+	#[allow(dead_code)]
+	struct DerivedAllowPolicy(TakeWeightCredit, AllowUnpaidExecutionFrom<IsInVec<AllowUnpaidFrom>>);
+	type HandWrittenAllowPolicy =
+		(TakeWeightCredit, AllowUnpaidExecutionFrom<IsInVec<AllowUnpaidFrom>>);
+
+	AllowUnpaidFrom::set(vec![Parent.into()]);
+	let forward = |xcm| DepositReserveAsset {
+		assets: All.into(),
+		dest: Parachain(1).into(),
+		xcm: Xcm(vec![xcm]),
+	};
+	for (origin, message, weight_credit) in [
+		(Location::parent(), vec![], Weight::zero()),
+		(Location::parent(), vec![ClearOrigin], Weight::zero()),
+		(Location::parent(), vec![Trap(1)], Weight::zero()),
+		(Location::parent(), vec![forward(ClearOrigin)], Weight::zero()),
+		(Location::parent(), vec![forward(forward(ClearOrigin))], Weight::zero()),
+		(Parachain(1).into(), vec![ClearOrigin], Weight::zero()),
+		(Parachain(1).into(), vec![ClearOrigin], Weight::from_parts(10, 10)),
+	] {
+		let mut derived = Xcm::<()>(message.clone());
+		let mut hand_written = Xcm::<()>(message);
+		let (mut derived_properties, mut hand_written_properties) =
+			(props(weight_credit), props(weight_credit));
+		assert_eq!(
+			DerivedDenyPolicy::<MaxHops>::deny_execution(
+				&origin,
+				derived.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut derived_properties,
+			),
+			HandWrittenDenyPolicy::deny_execution(
+				&origin,
+				hand_written.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut hand_written_properties,
+			),
+		);
+		assert_eq!(
+			DerivedAllowPolicy::should_execute(
+				&origin,
+				derived.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut derived_properties,
+			),
+			HandWrittenAllowPolicy::should_execute(
+				&origin,
+				hand_written.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut hand_written_properties,
+			),
+		);
+		assert_eq!(derived_properties, hand_written_properties);
+	}
+
+	let (mut derived_names, mut hand_written_names) = (vec![], vec![]);
+	DerivedAllowPolicy::names(&mut derived_names);
+	HandWrittenAllowPolicy::names(&mut hand_written_names);
+	assert_eq!(derived_names, hand_written_names);
+}
+
+#[test]
+fn check_response_weight_should_work() {
+	struct TestBudget;
+	impl ResponseWeightBudget for TestBudget {
+		fn response_weight_budget(origin: &Location, query_id: QueryId) -> Option<Weight> {
+			(*origin == Location::parent() && query_id == 42).then(|| Weight::from_parts(100, 100))
+		}
+	}
+
+	let assert_response = |query_id, response_weight, expected_result| {
+		let mut message = Xcm::<()>(vec![QueryResponse {
+			query_id,
+			response: Response::Null,
+			max_weight: response_weight,
+			querier: None,
+		}]);
+		assert_eq!(
+			CheckResponseWeight::<TestBudget>::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+
+	// within budget
+	assert_response(42, Weight::from_parts(100, 100), Ok(()));
+	assert_response(42, Weight::from_parts(10, 100), Ok(()));
+	// over budget
+	assert_response(
+		42,
+		Weight::from_parts(101, 100),
+		Err(ProcessMessageError::Overweight(Weight::from_parts(101, 100))),
+	);
+	assert_response(
+		42,
+		Weight::from_parts(100, 101),
+		Err(ProcessMessageError::Overweight(Weight::from_parts(100, 101))),
+	);
+	// unknown query
+	assert_response(43, Weight::from_parts(1_000, 1_000), Ok(()));
+}
+
+#[test]
+fn barriers_evaluated_should_work() {
+	AllowUnpaidFrom::set(vec![Parent.into()]);
+	type Barrier = (
+		TakeWeightCredit,
+		AllowTopLevelPaidExecutionFrom<IsInVec<AllowPaidFrom>>,
+		AllowUnpaidExecutionFrom<IsInVec<AllowUnpaidFrom>>,
+		AllowExplicitUnpaidExecutionFrom<IsInVec<AllowExplicitUnpaidFrom>>,
+	);
+
+	// two failing barriers, then a passing one
+	let mut properties = props(Weight::zero());
+	assert_eq!(
+		Barrier::should_execute(
+			&Parent.into(),
+			Xcm::<()>(vec![ClearOrigin]).inner_mut(),
+			Weight::from_parts(10, 10),
+			&mut properties,
+		),
+		Ok(())
+	);
+	assert_eq!(properties.barriers_evaluated, 3);
+
+	// all barriers failing
+	let mut properties = props(Weight::zero());
+	assert_eq!(
+		Barrier::should_execute(
+			&Parachain(1).into(),
+			Xcm::<()>(vec![ClearOrigin]).inner_mut(),
+			Weight::from_parts(10, 10),
+			&mut properties,
+		),
+		Err(ProcessMessageError::Unsupported)
+	);
+	assert_eq!(properties.barriers_evaluated, 4);
+}
+
+#[test]
+fn deny_self_forward_should_work() {
+	parameter_types! {
+		pub LocalLocation: Location = Here.into();
+	}
+	let assert_forward = |message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			DenySelfForward::<LocalLocation>::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+	let deposit_reserve =
+		|dest: Location| DepositReserveAsset { assets: All.into(), dest, xcm: Xcm(vec![]) };
+
+	// legitimate sibling destinations
+	assert_forward(vec![deposit_reserve((Parent, Parachain(2)).into())], Ok(()));
+	assert_forward(
+		vec![InitiateTeleport { assets: All.into(), dest: Parent.into(), xcm: Xcm(vec![]) }],
+		Ok(()),
+	);
+	// self-destination
+	assert_forward(vec![deposit_reserve(Here.into())], Err(ProcessMessageError::Unsupported));
+	assert_forward(
+		vec![InitiateReserveWithdraw {
+			assets: All.into(),
+			reserve: Here.into(),
+			xcm: Xcm(vec![]),
+		}],
+		Err(ProcessMessageError::Unsupported),
+	);
+	assert_forward(
+		vec![SetAppendix(Xcm(vec![deposit_reserve(Here.into())]))],
+		Err(ProcessMessageError::Unsupported),
+	);
+}
+
+#[test]
+fn require_monotonic_topic_should_work() {
+	parameter_types! {
+		pub static LastSequences: Vec<(Location, XcmHash)> = vec![];
+	}
+	struct TestSequenceStore;
+	impl SequenceStore for TestSequenceStore {
+		fn last_sequence(origin: &Location) -> Option<XcmHash> {
+			LastSequences::get().into_iter().find(|(o, _)| o == origin).map(|(_, s)| s)
+		}
+		fn note_sequence(origin: &Location, sequence: XcmHash) {
+			let mut sequences = LastSequences::get();
+			sequences.retain(|(o, _)| o != origin);
+			sequences.push((origin.clone(), sequence));
+			LastSequences::set(sequences);
+		}
+	}
+
+	let sequence = |n: u64| {
+		let mut topic = [0u8; 32];
+		topic[24..].copy_from_slice(&n.to_be_bytes());
+		topic
+	};
+	parameter_types! {
+		pub static Sequenced: Vec<Location> = vec![Parent.into(), Parachain(1).into()];
+	}
+	type Barrier = RequireMonotonicTopic<AllowAll, IsInVec<Sequenced>, TestSequenceStore>;
+	let assert_sequence = |origin: Location, message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			Barrier::should_execute(
+				&origin,
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+
+	// increasing
+	assert_sequence(Parent.into(), vec![ClearOrigin, SetTopic(sequence(1))], Ok(()));
+	assert_sequence(Parent.into(), vec![ClearOrigin, SetTopic(sequence(2))], Ok(()));
+	assert_sequence(Parent.into(), vec![ClearOrigin, SetTopic(sequence(256))], Ok(()));
+	// equal
+	assert_sequence(
+		Parent.into(),
+		vec![ClearOrigin, SetTopic(sequence(256))],
+		Err(ProcessMessageError::Unsupported),
+	);
+	// decreasing
+	assert_sequence(
+		Parent.into(),
+		vec![ClearOrigin, SetTopic(sequence(255))],
+		Err(ProcessMessageError::Unsupported),
+	);
+	assert_eq!(TestSequenceStore::last_sequence(&Parent.into()), Some(sequence(256)));
+	// sequences are tracked per origin
+	assert_sequence(Parachain(1).into(), vec![ClearOrigin, SetTopic(sequence(1))], Ok(()));
+	// a trailing `SetTopic` is required
+	assert_sequence(Parent.into(), vec![ClearOrigin], Err(ProcessMessageError::BadFormat));
+	// other origins are left to the inner barrier
+	assert_sequence(Parachain(2).into(), vec![ClearOrigin], Ok(()));
+	assert_eq!(TestSequenceStore::last_sequence(&Parachain(2).into()), None);
+
+	// messages rejected by the inner barrier do not consume their sequence number
+	let mut message = Xcm::<()>(vec![ClearOrigin, SetTopic(sequence(300))]);
+	assert_eq!(
+		RequireMonotonicTopic::<DenyAll, IsInVec<Sequenced>, TestSequenceStore>::should_execute(
+			&Parent.into(),
+			message.inner_mut(),
+			Weight::from_parts(10, 10),
+			&mut props(Weight::zero()),
+		),
+		Err(ProcessMessageError::Unsupported)
+	);
+	assert_eq!(TestSequenceStore::last_sequence(&Parent.into()), Some(sequence(256)));
+
+	// through the executor, behind the allow barriers of the test configuration
+	type Config = BarrierTestConfig<
+		RequireMonotonicTopic<TestBarrier, IsInVec<Sequenced>, TestSequenceStore>,
+	>;
+	Sequenced::set(vec![Parachain(1000).into()]);
+	let execute = |n| {
+		let message = Xcm::<TestCall>(vec![ClearOrigin, SetTopic(sequence(n))]);
+		let mut hash = fake_message_hash(&message);
+		XcmExecutor::<Config>::prepare_and_execute(
+			Parachain(1000),
+			message,
+			&mut hash,
+			Weight::from_parts(20, 20),
+			Weight::zero(),
+		)
+	};
+	// not allowed to execute, hence not consuming the sequence number
+	assert_eq!(execute(1), Outcome::Error { error: XcmError::Barrier });
+	assert_eq!(TestSequenceStore::last_sequence(&Parachain(1000).into()), None);
+	AllowUnpaidFrom::set(vec![Parachain(1000).into()]);
+	assert_eq!(execute(1), Outcome::Complete { used: Weight::from_parts(20, 20) });
+	assert_eq!(execute(1), Outcome::Error { error: XcmError::Barrier });
+	assert_eq!(execute(2), Outcome::Complete { used: Weight::from_parts(20, 20) });
+}
+
+#[test]
+fn deny_pallet_introspection_from_should_work() {
+	parameter_types! {
+		pub UntrustedOrigins: Vec<Location> = vec![Parachain(1).into()];
+	}
+	let assert_introspection =
+		|origin: Location, message: Vec<Instruction<()>>, expected_result| {
+			let mut message = Xcm::<()>(message);
+			assert_eq!(
+				DenyPalletIntrospectionFrom::<IsInVec<UntrustedOrigins>>::deny_execution(
+					&origin,
+					message.inner_mut(),
+					Weight::from_parts(10, 10),
+					&mut props(Weight::zero()),
+				),
+				expected_result
+			);
+		};
+	let query_pallet = || QueryPallet {
+		module_name: b"System".to_vec(),
+		response_info: QueryResponseInfo {
+			destination: Parent.into(),
+			query_id: 1,
+			max_weight: Weight::zero(),
+		},
+	};
+	let expect_pallet = || ExpectPallet {
+		index: 0,
+		name: b"System".to_vec(),
+		module_name: b"frame_system".to_vec(),
+		crate_major: 1,
+		min_crate_minor: 0,
+	};
+
+	// trusted origin
+	assert_introspection(Parent.into(), vec![query_pallet()], Ok(()));
+	assert_introspection(Parent.into(), vec![expect_pallet()], Ok(()));
+	// untrusted origin
+	assert_introspection(Parachain(1).into(), vec![ClearOrigin], Ok(()));
+	assert_introspection(
+		Parachain(1).into(),
+		vec![query_pallet()],
+		Err(ProcessMessageError::Unsupported),
+	);
+	assert_introspection(
+		Parachain(1).into(),
+		vec![SetErrorHandler(Xcm(vec![expect_pallet()]))],
+		Err(ProcessMessageError::Unsupported),
+	);
+}
+
+#[test]
+fn grant_dimensional_credit_should_work() {
+	parameter_types! {
+		pub TrustedOrigins: Vec<Location> = vec![Parent.into()];
+		pub const RefTimeCredit: u64 = 10;
+		pub const ProofSizeCredit: u64 = 20;
+		pub const NoCredit: u64 = 0;
+	}
+	fn granted<Barrier: ShouldExecute>(origin: Location, weight_credit: Weight) -> Weight {
+		let mut properties = props(weight_credit);
+		assert_eq!(
+			Barrier::should_execute(
+				&origin,
+				Xcm::<()>(vec![ClearOrigin]).inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut properties,
+			),
+			Err(ProcessMessageError::Unsupported)
+		);
+		properties.weight_credit
+	}
+	type GrantRefTime = GrantDimensionalCredit<IsInVec<TrustedOrigins>, RefTimeCredit, NoCredit>;
+	type GrantProofSize =
+		GrantDimensionalCredit<IsInVec<TrustedOrigins>, NoCredit, ProofSizeCredit>;
+	type GrantBoth =
+		GrantDimensionalCredit<IsInVec<TrustedOrigins>, RefTimeCredit, ProofSizeCredit>;
+
+	// independent per-dimension credit
+	assert_eq!(
+		granted::<GrantRefTime>(Parent.into(), Weight::from_parts(1, 1)),
+		Weight::from_parts(11, 1)
+	);
+	assert_eq!(
+		granted::<GrantProofSize>(Parent.into(), Weight::from_parts(1, 1)),
+		Weight::from_parts(1, 21)
+	);
+	// each dimension saturates independently
+	assert_eq!(
+		granted::<GrantBoth>(Parent.into(), Weight::from_parts(u64::MAX - 5, 1)),
+		Weight::from_parts(u64::MAX, 21)
+	);
+	// no credit for other origins
+	assert_eq!(granted::<GrantBoth>(Parachain(1).into(), Weight::zero()), Weight::zero());
+
+	// free `proof_size`, but `ref_time` must be paid for
+	type Barrier = (
+		GrantDimensionalCredit<IsInVec<TrustedOrigins>, NoCredit, ProofSizeCredit>,
+		TakeWeightCredit,
+	);
+	let mut properties = props(Weight::from_parts(10, 0));
+	assert_eq!(
+		Barrier::should_execute(
+			&Parent.into(),
+			Xcm::<()>(vec![ClearOrigin]).inner_mut(),
+			Weight::from_parts(10, 20),
+			&mut properties,
+		),
+		Ok(())
+	);
+	assert_eq!(properties.weight_credit, Weight::zero());
+	let mut properties = props(Weight::zero());
+	assert_eq!(
+		Barrier::should_execute(
+			&Parent.into(),
+			Xcm::<()>(vec![ClearOrigin]).inner_mut(),
+			Weight::from_parts(10, 20),
+			&mut properties,
+		),
+		Err(ProcessMessageError::Unsupported)
+	);
+}
+
+#[test]
+fn require_fees_in_holding_should_work() {
+	let assert_fees = |message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			RequireFeesInHolding::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+	let buy_execution =
+		|fees: Asset| BuyExecution { fees, weight_limit: Limited(Weight::from_parts(10, 10)) };
+
+	// fees present
+	assert_fees(
+		vec![WithdrawAsset((Parent, 100).into()), buy_execution((Parent, 100).into())],
+		Ok(()),
+	);
+	assert_fees(
+		vec![
+			ReserveAssetDeposited((Parent, 60).into()),
+			WithdrawAsset((Parent, 40).into()),
+			buy_execution((Parent, 100).into()),
+		],
+		Ok(()),
+	);
+	// fees absent
+	assert_fees(vec![buy_execution((Parent, 100).into())], Err(ProcessMessageError::BadFormat));
+	assert_fees(
+		vec![WithdrawAsset((Parent, 99).into()), buy_execution((Parent, 100).into())],
+		Err(ProcessMessageError::BadFormat),
+	);
+	assert_fees(
+		vec![WithdrawAsset((Here, 100).into()), buy_execution((Parent, 100).into())],
+		Err(ProcessMessageError::BadFormat),
+	);
+	// fees must be loaded before being used
+	assert_fees(
+		vec![buy_execution((Parent, 100).into()), WithdrawAsset((Parent, 100).into())],
+		Err(ProcessMessageError::BadFormat),
+	);
+}
+
+#[test]
+fn when_should_work() {
+	struct StartsWithClearOrigin;
+	impl MessagePredicate for StartsWithClearOrigin {
+		fn matches<Call>(_origin: &Location, instructions: &[Instruction<Call>]) -> bool {
+			matches!(instructions.first(), Some(ClearOrigin))
+		}
+	}
+	type Barrier = When<StartsWithClearOrigin, AllowUnpaidExecutionFrom<IsInVec<AllowUnpaidFrom>>>;
+	AllowUnpaidFrom::set(vec![Parent.into()]);
+
+	let assert_when = |origin: Location, message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			Barrier::should_execute(
+				&origin,
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+
+	// matching, hence `B` runs and decides
+	assert_when(Parent.into(), vec![ClearOrigin], Ok(()));
+	assert_when(Parachain(1).into(), vec![ClearOrigin], Err(ProcessMessageError::Unsupported));
+	// not matching, hence `B` is skipped
+	assert_when(Parent.into(), vec![RefundSurplus], Err(ProcessMessageError::Unsupported));
+}
+
+#[test]
+fn deny_forwarding_in_error_handler_should_work() {
+	let assert_handler = |message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			DenyForwardingInErrorHandler::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+	let forward = || DepositReserveAsset {
+		assets: All.into(),
+		dest: (Parent, Parachain(2)).into(),
+		xcm: Xcm(vec![]),
+	};
+
+	// benign handlers
+	assert_handler(
+		vec![
+			SetErrorHandler(Xcm(vec![RefundSurplus])),
+			SetAppendix(Xcm(vec![DepositAsset { assets: All.into(), beneficiary: Here.into() }])),
+		],
+		Ok(()),
+	);
+	// forwarding outside of handlers is fine
+	assert_handler(vec![forward()], Ok(()));
+	// forwarding-containing handlers
+	assert_handler(
+		vec![SetErrorHandler(Xcm(vec![forward()]))],
+		Err(ProcessMessageError::Unsupported),
+	);
+	assert_handler(vec![SetAppendix(Xcm(vec![forward()]))], Err(ProcessMessageError::Unsupported));
+	assert_handler(
+		vec![SetAppendix(Xcm(vec![SetErrorHandler(Xcm(vec![forward()]))]))],
+		Err(ProcessMessageError::Unsupported),
+	);
+}
+
+#[test]
+fn should_execute_for_arrays_should_work() {
+	// Passes only once evaluated as the third barrier.
+	struct PassOnThirdEvaluation;
+	impl ShouldExecute for PassOnThirdEvaluation {
+		fn should_execute<RuntimeCall>(
+			_origin: &Location,
+			_instructions: &mut [Instruction<RuntimeCall>],
+			_max_weight: Weight,
+			properties: &mut Properties,
+		) -> Result<(), ProcessMessageError> {
+			ensure!(properties.barriers_evaluated >= 3, ProcessMessageError::Unsupported);
+			Ok(())
+		}
+	}
+	fn evaluate<Barrier: ShouldExecute>() -> (Result<(), ProcessMessageError>, u32) {
+		let mut properties = props(Weight::zero());
+		let result = Barrier::should_execute(
+			&Parent.into(),
+			Xcm::<()>(vec![ClearOrigin]).inner_mut(),
+			Weight::from_parts(10, 10),
+			&mut properties,
+		);
+		(result, properties.barriers_evaluated)
+	}
+
+	assert_eq!(
+		evaluate::<[PassOnThirdEvaluation; 0]>(),
+		(Err(ProcessMessageError::Unsupported), 0)
+	);
+	assert_eq!(
+		evaluate::<[PassOnThirdEvaluation; 2]>(),
+		(Err(ProcessMessageError::Unsupported), 2)
+	);
+	assert_eq!(evaluate::<[PassOnThirdEvaluation; 3]>(), (Ok(()), 3));
+	assert_eq!(evaluate::<[PassOnThirdEvaluation; 5]>(), (Ok(()), 3));
+
+	let mut names = vec![];
+	<[TakeWeightCredit; 3]>::names(&mut names);
+	assert_eq!(names, vec![core::any::type_name::<TakeWeightCredit>(); 3]);
+}
+
+#[test]
+fn deny_late_universal_origin_should_work() {
+	let assert_universal_origin = |message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			DenyLateUniversalOrigin::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+
+	// leading
+	assert_universal_origin(vec![UniversalOrigin(GlobalConsensus(Kusama)), ClearOrigin], Ok(()));
+	assert_universal_origin(vec![ClearOrigin], Ok(()));
+	// non-leading
+	assert_universal_origin(
+		vec![ClearOrigin, UniversalOrigin(GlobalConsensus(Kusama))],
+		Err(ProcessMessageError::BadFormat),
+	);
+	assert_universal_origin(
+		vec![UniversalOrigin(GlobalConsensus(Kusama)), UniversalOrigin(GlobalConsensus(Polkadot))],
+		Err(ProcessMessageError::BadFormat),
+	);
+}
+
+#[test]
+fn global_budget_should_work() {
+	parameter_types! {
+		pub static CurrentBlock: u64 = 1;
+		pub static Drawn: Option<(u64, Weight)> = None;
+		pub const PerBlock: Weight = Weight::from_parts(25, 25);
+	}
+	struct TestStore;
+	impl BlockBudgetStore for TestStore {
+		type BlockNumber = u64;
+		fn current_block_number() -> u64 {
+			CurrentBlock::get()
+		}
+		fn drawn() -> Option<(u64, Weight)> {
+			Drawn::get()
+		}
+		fn set_drawn(block: u64, weight: Weight) {
+			Drawn::set(Some((block, weight)));
+		}
+	}
+
+	let draw = |max_weight| {
+		GlobalBudget::<TestStore, PerBlock>::should_execute(
+			&Parent.into(),
+			Xcm::<()>(vec![ClearOrigin]).inner_mut(),
+			max_weight,
+			&mut props(Weight::zero()),
+		)
+	};
+
+	// draining the budget across messages of the same block
+	assert_eq!(draw(Weight::from_parts(10, 10)), Ok(()));
+	assert_eq!(draw(Weight::from_parts(10, 5)), Ok(()));
+	assert_eq!(
+		draw(Weight::from_parts(10, 1)),
+		Err(ProcessMessageError::Overweight(Weight::from_parts(10, 1)))
+	);
+	assert_eq!(draw(Weight::from_parts(5, 10)), Ok(()));
+	assert_eq!(
+		draw(Weight::from_parts(0, 1)),
+		Err(ProcessMessageError::Overweight(Weight::from_parts(0, 1)))
+	);
+	assert_eq!(Drawn::get(), Some((1, Weight::from_parts(25, 25))));
+
+	// the budget is replenished in the next block
+	CurrentBlock::set(2);
+	assert_eq!(draw(Weight::from_parts(25, 25)), Ok(()));
+	assert_eq!(Drawn::get(), Some((2, Weight::from_parts(25, 25))));
+}
+
+#[test]
+fn require_local_beneficiary_should_work() {
+	struct IsLocal;
+	impl Contains<Location> for IsLocal {
+		fn contains(location: &Location) -> bool {
+			location.parent_count() == 0
+		}
+	}
+
+	let assert_beneficiary = |beneficiary: Location, expected_result| {
+		let mut message = Xcm::<()>(vec![
+			WithdrawAsset((Parent, 100).into()),
+			SetErrorHandler(Xcm(vec![DepositAsset {
+				assets: All.into(),
+				beneficiary: AccountIndex64 { network: None, index: 1 }.into(),
+			}])),
+			DepositAsset { assets: All.into(), beneficiary },
+		]);
+		assert_eq!(
+			RequireLocalBeneficiary::<IsLocal>::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+
+	// local beneficiaries
+	assert_beneficiary(AccountIndex64 { network: None, index: 2 }.into(), Ok(()));
+	assert_beneficiary(Here.into(), Ok(()));
+	// non-local beneficiaries
+	assert_beneficiary(
+		Location::new(1, [Parachain(2), AccountIndex64 { network: None, index: 2 }]),
+		Err(ProcessMessageError::Unsupported),
+	);
+	assert_beneficiary(Parent.into(), Err(ProcessMessageError::Unsupported));
+}
+
+#[test]
+fn deny_zero_topic_should_work() {
+	let assert_topic = |message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			DenyZeroTopic::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+
+	assert_topic(vec![ClearOrigin], Ok(()));
+	assert_topic(vec![ClearOrigin, SetTopic([1; 32])], Ok(()));
+	assert_topic(vec![ClearOrigin, SetTopic([0; 32])], Err(ProcessMessageError::BadFormat));
+}
+
+#[test]
+fn combined_deny_allow_config_should_work() {
+	type Deny = (DenyTrap, DenyLateUniversalOrigin);
+	type Allow = (TakeWeightCredit, AllowUnpaidExecutionFrom<IsInVec<AllowUnpaidFrom>>);
+	type Barrier = DenyThenTry<Deny, Allow>;
+	AllowUnpaidFrom::set(vec![Parent.into()]);
+
+	let assert_barrier = |origin: Location, message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			Barrier::should_execute(
+				&origin,
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+
+	// accepted
+	assert_barrier(Parent.into(), vec![RefundSurplus], Ok(()));
+	// denied even though allowed
+	assert_barrier(Parent.into(), vec![Trap(1)], Err(ProcessMessageError::BadFormat));
+	assert_barrier(
+		Parent.into(),
+		vec![ClearOrigin, UniversalOrigin(GlobalConsensus(Kusama))],
+		Err(ProcessMessageError::BadFormat),
+	);
+	// not denied, but not allowed either
+	assert_barrier(Parachain(1).into(), vec![RefundSurplus], Err(ProcessMessageError::Unsupported));
+}
+
+#[test]
+fn weight_sanity_should_work() {
+	parameter_types! {
+		pub const MaxFactor: u64 = 2;
+	}
+	type TestWeigher = FixedWeightBounds<UnitWeightCost, TestCall, MaxInstructions>;
+	type Barrier = WeightSanity<
+		TestWeigher,
+		TestCall,
+		MaxFactor,
+		AllowUnpaidExecutionFrom<IsInVec<AllowUnpaidFrom>>,
+	>;
+	AllowUnpaidFrom::set(vec![Parent.into()]);
+
+	let assert_weight = |origin: Location, max_weight, expected_result| {
+		let mut message = Xcm::<()>(vec![ClearOrigin, RefundSurplus]);
+		assert_eq!(
+			Barrier::should_execute(
+				&origin,
+				message.inner_mut(),
+				max_weight,
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+
+	// reasonable weights, left to the inner barrier
+	assert_weight(Parent.into(), Weight::from_parts(20, 20), Ok(()));
+	assert_weight(Parent.into(), Weight::from_parts(40, 40), Ok(()));
+	assert_weight(
+		Parachain(1).into(),
+		Weight::from_parts(20, 20),
+		Err(ProcessMessageError::Unsupported),
+	);
+	// wildly over-declared weights
+	assert_weight(
+		Parent.into(),
+		Weight::from_parts(41, 40),
+		Err(ProcessMessageError::Overweight(Weight::from_parts(41, 40))),
+	);
+	assert_weight(
+		Parent.into(),
+		Weight::from_parts(10, 1_000_000),
+		Err(ProcessMessageError::Overweight(Weight::from_parts(10, 1_000_000))),
+	);
+	// messages which cannot be weighed
+	MaxInstructions::set(1);
+	assert_weight(Parent.into(), Weight::from_parts(20, 20), Err(ProcessMessageError::BadFormat));
+}
+
+#[test]
+fn last_rejection_should_work() {
+	AllowUnpaidFrom::set(vec![Parent.into()]);
+	type Allow = (TakeWeightCredit, AllowUnpaidExecutionFrom<IsInVec<AllowUnpaidFrom>>);
+	let evaluate = |origin: Location, message: Vec<Instruction<()>>| {
+		let mut properties = props(Weight::zero());
+		let result = DenyThenTry::<(DenyTrap, DenyEmpty), Allow>::should_execute(
+			&origin,
+			Xcm::<()>(message).inner_mut(),
+			Weight::from_parts(10, 10),
+			&mut properties,
+		);
+		(result, properties.last_rejection)
+	};
+
+	// full rejection by the allow tuple
+	assert_eq!(
+		evaluate(Parachain(1).into(), vec![ClearOrigin]),
+		(
+			Err(ProcessMessageError::Unsupported),
+			Some(core::any::type_name::<AllowUnpaidExecutionFrom<IsInVec<AllowUnpaidFrom>>>())
+		)
+	);
+	// rejection by the deny tuple
+	assert_eq!(
+		evaluate(Parent.into(), vec![Trap(1)]),
+		(Err(ProcessMessageError::BadFormat), Some(core::any::type_name::<DenyTrap>()))
+	);
+	// not set when passing
+	assert_eq!(evaluate(Parent.into(), vec![ClearOrigin]), (Ok(()), None));
+}
+
+#[test]
+fn allow_teleport_of_should_work() {
+	// Only the relay chain native asset may be teleported, and only from the relay chain.
+	struct TrustedTeleports;
+	impl ContainsPair<Asset, Location> for TrustedTeleports {
+		fn contains(asset: &Asset, origin: &Location) -> bool {
+			asset.id == AssetId(Parent.into()) && *origin == Location::parent()
+		}
+	}
+
+	let assert_teleport = |origin: Location, assets: Assets, expected_result| {
+		let mut message = Xcm::<()>(vec![
+			ReceiveTeleportedAsset(assets),
+			ClearOrigin,
+			DepositAsset { assets: All.into(), beneficiary: Here.into() },
+		]);
+		assert_eq!(
+			AllowTeleportOf::<TrustedTeleports>::deny_execution(
+				&origin,
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+
+	// trusted pair
+	assert_teleport(Parent.into(), (Parent, 100).into(), Ok(()));
+	// untrusted pair
+	assert_teleport(
+		Parachain(1).into(),
+		(Parent, 100).into(),
+		Err(ProcessMessageError::Unsupported),
+	);
+	// reserve-only asset incorrectly teleported
+	let reserve_only: Asset = ((Parent, Parachain(1000), GeneralIndex(1)), 100).into();
+	assert_teleport(
+		Parent.into(),
+		reserve_only.clone().into(),
+		Err(ProcessMessageError::Unsupported),
+	);
+	assert_teleport(
+		Parent.into(),
+		vec![(Parent, 100).into(), reserve_only].into(),
+		Err(ProcessMessageError::Unsupported),
+	);
+}
+
+#[test]
+fn ring_recorder_should_evict_oldest_rejections() {
+	let mut recorder = RingRecorder::<2>::new();
+	assert_eq!(recorder.rejections().count(), 0);
+
+	recorder.on_rejection("first", ProcessMessageError::Unsupported);
+	assert_eq!(
+		recorder.rejections().collect::<Vec<_>>(),
+		vec![("first", ProcessMessageError::Unsupported)]
+	);
+
+	recorder.on_rejection("second", ProcessMessageError::BadFormat);
+	recorder.on_rejection("third", ProcessMessageError::Overweight(Weight::from_parts(1, 1)));
+	assert_eq!(
+		recorder.rejections().collect::<Vec<_>>(),
+		vec![
+			("second", ProcessMessageError::BadFormat),
+			("third", ProcessMessageError::Overweight(Weight::from_parts(1, 1))),
+		]
+	);
+
+	recorder.on_rejection("fourth", ProcessMessageError::Yield);
+	recorder.on_rejection("fifth", ProcessMessageError::Corrupt);
+	assert_eq!(
+		recorder.rejections().collect::<Vec<_>>(),
+		vec![("fourth", ProcessMessageError::Yield), ("fifth", ProcessMessageError::Corrupt)]
+	);
+
+	// a zero-capacity recorder records nothing
+	let mut recorder = RingRecorder::<0>::new();
+	recorder.on_rejection("first", ProcessMessageError::Unsupported);
+	assert_eq!(recorder.rejections().count(), 0);
+}
+
+#[test]
+fn require_fee_before_transact_should_work() {
+	let assert_fee = |message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			RequireFeeBeforeTransact::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+	let transact = || Transact {
+		origin_kind: OriginKind::Native,
+		fallback_max_weight: None,
+		call: Vec::new().into(),
+	};
+	let fees: Asset = (Here, 100).into();
+
+	// fee before transact
+	assert_fee(
+		vec![
+			WithdrawAsset(fees.clone().into()),
+			BuyExecution { fees: fees.clone(), weight_limit: Unlimited },
+			transact(),
+		],
+		Ok(()),
+	);
+	assert_fee(
+		vec![WithdrawAsset(fees.clone().into()), PayFees { asset: fees.clone() }, transact()],
+		Ok(()),
+	);
+	// transact before fee
+	assert_fee(
+		vec![
+			WithdrawAsset(fees.clone().into()),
+			transact(),
+			BuyExecution { fees, weight_limit: Unlimited },
+		],
+		Err(ProcessMessageError::Unsupported),
+	);
+	assert_fee(vec![transact()], Err(ProcessMessageError::Unsupported));
+	// unpaid
+	assert_fee(
+		vec![UnpaidExecution { weight_limit: Unlimited, check_origin: None }, transact()],
+		Ok(()),
+	);
+	// no transact at all
+	assert_fee(vec![ClearOrigin], Ok(()));
+}
+
+#[test]
+fn all_should_require_every_barrier_to_pass() {
+	AllowUnpaidFrom::set(vec![Parent.into()]);
+	type Unpaid = AllowUnpaidExecutionFrom<IsInVec<AllowUnpaidFrom>>;
+
+	fn assert_all<Barrier: AllBarriers>(
+		origin: Location,
+		expected_result: Result<(), ProcessMessageError>,
+		expected_credit: Weight,
+	) {
+		let mut message = Xcm::<()>(vec![ClearOrigin]);
+		let mut properties = props(Weight::from_parts(20, 20));
+		assert_eq!(
+			crate::All::<Barrier>::should_execute(
+				&origin,
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut properties,
+			),
+			expected_result
+		);
+		assert_eq!(properties.weight_credit, expected_credit);
+	}
+
+	// all pass: mutations accumulate
+	assert_all::<(TakeWeightCredit, Unpaid)>(Parent.into(), Ok(()), Weight::from_parts(10, 10));
+	assert_all::<(TakeWeightCredit, TakeWeightCredit)>(Parent.into(), Ok(()), Weight::zero());
+	// first fails
+	assert_all::<(Unpaid, TakeWeightCredit)>(
+		Parachain(1).into(),
+		Err(ProcessMessageError::Unsupported),
+		Weight::from_parts(20, 20),
+	);
+	// middle fails: mutations of the preceding barrier are discarded
+	assert_all::<(TakeWeightCredit, Unpaid, TakeWeightCredit)>(
+		Parachain(1).into(),
+		Err(ProcessMessageError::Unsupported),
+		Weight::from_parts(20, 20),
+	);
+	// last fails with its own error
+	assert_all::<(TakeWeightCredit, TakeWeightCredit, TakeWeightCredit)>(
+		Parent.into(),
+		Err(ProcessMessageError::Overweight(Weight::from_parts(10, 10))),
+		Weight::from_parts(20, 20),
+	);
+}
+
+#[test]
+fn deny_multiple_buy_execution_should_work() {
+	let assert_buys = |buys: usize, expected_result| {
+		let fees: Asset = (Here, 100).into();
+		let mut message = Xcm::<()>(vec![WithdrawAsset(fees.clone().into())]);
+		message.0.extend(
+			(0..buys).map(|_| BuyExecution { fees: fees.clone(), weight_limit: Unlimited }),
+		);
+		message.0.push(ClearOrigin);
+		assert_eq!(
+			DenyMultipleBuyExecution::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+
+	assert_buys(0, Ok(()));
+	assert_buys(1, Ok(()));
+	assert_buys(2, Err(ProcessMessageError::BadFormat));
+}
+
+#[test]
+fn override_for_should_work() {
+	parameter_types! {
+		pub ProblemOrigins: Vec<Location> = vec![Parachain(1).into()];
+		pub UnpaidOrigins: Vec<Location> = vec![Parent.into(), Parachain(1).into()];
+	}
+	type Barrier = OverrideFor<
+		IsInVec<ProblemOrigins>,
+		TakeWeightCredit,
+		AllowUnpaidExecutionFrom<IsInVec<UnpaidOrigins>>,
+	>;
+
+	let assert_override = |origin: Location, credit, expected_result, expected_credit| {
+		let mut message = Xcm::<()>(vec![ClearOrigin]);
+		let mut properties = props(credit);
+		assert_eq!(
+			Barrier::should_execute(
+				&origin,
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut properties,
+			),
+			expected_result
+		);
+		assert_eq!(properties.weight_credit, expected_credit);
+	};
+
+	// matched origin takes the strict path, and has its credit taken
+	assert_override(
+		Parachain(1).into(),
+		Weight::from_parts(15, 15),
+		Ok(()),
+		Weight::from_parts(5, 5),
+	);
+	// matched origin is not let through by the default path
+	assert_override(
+		Parachain(1).into(),
+		Weight::zero(),
+		Err(ProcessMessageError::Overweight(Weight::from_parts(10, 10))),
+		Weight::zero(),
+	);
+	// other origins take the default path, leaving their credit untouched
+	assert_override(Parent.into(), Weight::from_parts(15, 15), Ok(()), Weight::from_parts(15, 15));
+	assert_override(
+		Parachain(2).into(),
+		Weight::from_parts(15, 15),
+		Err(ProcessMessageError::Unsupported),
+		Weight::from_parts(15, 15),
+	);
+}
+
+#[test]
+fn validate_set_hints_should_work() {
+	struct LocalClaimers;
+	impl Contains<Hint> for LocalClaimers {
+		fn contains(hint: &Hint) -> bool {
+			match hint {
+				AssetClaimer { location } => location.parents == 0,
+			}
+		}
+	}
+	let assert_hints = |message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			ValidateSetHints::<LocalClaimers>::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+	let set_claimer = |location: Location| SetHints {
+		hints: vec![AssetClaimer { location }].try_into().unwrap(),
+	};
+
+	// allowed hints
+	assert_hints(vec![ClearOrigin], Ok(()));
+	assert_hints(vec![SetHints { hints: Default::default() }], Ok(()));
+	assert_hints(vec![set_claimer(AccountId32 { network: None, id: [1; 32] }.into())], Ok(()));
+	// disallowed hints
+	assert_hints(
+		vec![ClearOrigin, set_claimer((Parent, Parachain(1000)).into())],
+		Err(ProcessMessageError::Unsupported),
+	);
+	assert_hints(
+		vec![SetAppendix(Xcm(vec![set_claimer(Parent.into())]))],
+		Err(ProcessMessageError::Unsupported),
+	);
+}
+
+#[test]
+fn deny_unlimited_paid_should_work() {
+	let assert_limit = |message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			DenyUnlimitedPaid::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+	let fees: Asset = (Here, 100).into();
+
+	// limited paid
+	assert_limit(
+		vec![
+			WithdrawAsset(fees.clone().into()),
+			BuyExecution { fees: fees.clone(), weight_limit: Limited(Weight::from_parts(10, 10)) },
+		],
+		Ok(()),
+	);
+	// unlimited paid
+	assert_limit(
+		vec![WithdrawAsset(fees.clone().into()), BuyExecution { fees, weight_limit: Unlimited }],
+		Err(ProcessMessageError::Unsupported),
+	);
+	// unlimited unpaid
+	assert_limit(vec![UnpaidExecution { weight_limit: Unlimited, check_origin: None }], Ok(()));
+}
+
+#[test]
+fn require_nested_fees_should_work() {
+	let assert_nested = |message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			RequireNestedFees::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+	let deposit_reserve = |xcm: Vec<Instruction<()>>| DepositReserveAsset {
+		assets: All.into(),
+		dest: Parent.into(),
+		xcm: Xcm(xcm),
+	};
+	let fees: Asset = (Parent, 100).into();
+	let beneficiary: Location = AccountId32 { network: None, id: [1; 32] }.into();
+
+	// nested paid
+	assert_nested(
+		vec![deposit_reserve(vec![
+			BuyExecution { fees: fees.clone(), weight_limit: Unlimited },
+			DepositAsset { assets: All.into(), beneficiary: beneficiary.clone() },
+		])],
+		Ok(()),
+	);
+	// nested unpaid
+	assert_nested(
+		vec![deposit_reserve(vec![
+			UnpaidExecution { weight_limit: Unlimited, check_origin: None },
+			DepositAsset { assets: All.into(), beneficiary: beneficiary.clone() },
+		])],
+		Ok(()),
+	);
+	// nested without fees
+	assert_nested(
+		vec![deposit_reserve(vec![DepositAsset {
+			assets: All.into(),
+			beneficiary: beneficiary.clone(),
+		}])],
+		Err(ProcessMessageError::Unsupported),
+	);
+	assert_nested(
+		vec![SetAppendix(Xcm(vec![InitiateTeleport {
+			assets: All.into(),
+			dest: Parent.into(),
+			xcm: Xcm(vec![]),
+		}]))],
+		Err(ProcessMessageError::Unsupported),
+	);
+	// remote fees paid by the executor
+	assert_nested(
+		vec![InitiateTransfer {
+			destination: Parent.into(),
+			remote_fees: Some(xcm::latest::AssetTransferFilter::Teleport(fees.into())),
+			preserve_origin: false,
+			assets: Default::default(),
+			remote_xcm: Xcm(vec![DepositAsset { assets: All.into(), beneficiary }]),
+		}],
+		Ok(()),
+	);
+}
+
+#[test]
+fn max_nested_size_should_work() {
+	parameter_types! {
+		pub const Limit: u32 = 64;
+	}
+	let assert_nested = |message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			MaxNestedSize::<Limit>::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+	let teleport = |xcm: Vec<Instruction<()>>| InitiateTeleport {
+		assets: All.into(),
+		dest: Parent.into(),
+		xcm: Xcm(xcm),
+	};
+
+	// small nested payloads
+	assert_nested(vec![teleport(vec![])], Ok(()));
+	assert_nested(vec![teleport(vec![ClearOrigin; 60])], Ok(()));
+	// oversized nested payloads
+	assert_nested(vec![teleport(vec![ClearOrigin; 70])], Err(ProcessMessageError::Unsupported));
+	assert_nested(
+		vec![SetErrorHandler(Xcm(vec![teleport(vec![SetTopic([1; 32]); 2])]))],
+		Err(ProcessMessageError::Unsupported),
+	);
+}
+
+#[test]
+fn assert_barrier_macro_should_work() {
+	assert_barrier!(AllowAll, Parent, [], expects Ok);
+	assert_barrier!(AllowAll, Parachain(1), [ClearOrigin, Trap(0)], expects Ok);
+	assert_barrier!(DenyAll, Parent, [ClearOrigin], expects Err(Unsupported));
+	assert_barrier!(
+		TakeWeightCredit,
+		Parent,
+		[ClearOrigin],
+		max_weight: Weight::from_parts(10, 10),
+		expects Err(Overweight(Weight::from_parts(10, 10)))
+	);
+	assert_barrier!(TakeWeightCredit, Parent, [ClearOrigin], expects Ok);
+	assert_barrier!(AllowAll, Parent, [ClearOrigin], call: TestCall, expects Ok);
+	assert_barrier!(
+		DenyAll,
+		Parent,
+		[ClearOrigin],
+		call: TestCall,
+		max_weight: Weight::from_parts(10, 10),
+		expects Err(Unsupported)
+	);
+	assert_barrier!(deny: DenyTrap, Parent, [Trap(1)], expects Err(BadFormat));
+	assert_barrier!(deny: (DenyTrap, DenyEmpty), Parent, [ClearOrigin], expects Ok);
+	assert_barrier!(deny: DenyEmpty, Parent, [], call: TestCall, expects Err(BadFormat));
+	assert_barrier!(
+		deny: DenyUnlimitedPaid,
+		Parent,
+		[BuyExecution { fees: (Parent, 1).into(), weight_limit: Unlimited }],
+		max_weight: Weight::from_parts(10, 10),
+		expects Err(Unsupported)
+	);
+}
+
+#[test]
+#[should_panic]
+fn assert_barrier_macro_should_catch_wrong_verdict() {
+	assert_barrier!(DenyAll, Parent, [ClearOrigin], expects Ok);
+}
+
+#[test]
+fn transact_within_budget_should_work() {
+	parameter_types! {
+		pub static Remaining: Weight = Weight::zero();
+	}
+	type Barrier = TransactWithinBudget<Remaining, AllowAll>;
+	let transact = |weight: u64| Transact {
+		origin_kind: OriginKind::Native,
+		fallback_max_weight: Some(Weight::from_parts(weight, weight)),
+		call: Vec::new().into(),
+	};
+
+	Remaining::set(Weight::from_parts(10, 10));
+	// single transact
+	assert_barrier!(Barrier, Parent, [transact(10)], expects Ok);
+	assert_barrier!(
+		Barrier,
+		Parent,
+		[transact(11)],
+		expects Err(Overweight(Weight::from_parts(11, 11)))
+	);
+	// multiple transacts, including nested ones
+	assert_barrier!(Barrier, Parent, [transact(4), transact(6)], expects Ok);
+	assert_barrier!(
+		Barrier,
+		Parent,
+		[transact(4), SetAppendix(Xcm(vec![transact(7)]))],
+		expects Err(Overweight(Weight::from_parts(11, 11)))
+	);
+
+	// against a varying remaining budget
+	Remaining::set(Weight::from_parts(20, 20));
+	assert_barrier!(Barrier, Parent, [transact(4), SetAppendix(Xcm(vec![transact(7)]))], expects Ok);
+	Remaining::set(Weight::zero());
+	assert_barrier!(Barrier, Parent, [ClearOrigin], expects Ok);
+	assert_barrier!(
+		Barrier,
+		Parent,
+		[transact(1)],
+		expects Err(Overweight(Weight::from_parts(1, 1)))
+	);
+
+	// transacts without a declared weight cannot be bounded
+	let unweighed = Transact {
+		origin_kind: OriginKind::Native,
+		fallback_max_weight: None,
+		call: Vec::new().into(),
+	};
+	Remaining::set(Weight::from_parts(20, 20));
+	assert_barrier!(Barrier, Parent, [unweighed.clone()], expects Err(Unsupported));
+	assert_barrier!(
+		Barrier,
+		Parent,
+		[transact(4), SetAppendix(Xcm(vec![unweighed]))],
+		expects Err(Unsupported)
+	);
+}
+
+#[test]
+fn score_risk_should_work() {
+	parameter_types! {
+		pub ForeignOrigins: Vec<Location> = vec![(Parent, Parent, GlobalConsensus(Kusama)).into()];
+	}
+	type Barrier = (ScoreRisk<CompositionRisk<IsInVec<ForeignOrigins>>>, TakeWeightCredit);
+
+	let assert_score = |origin: Location, message: Vec<Instruction<()>>, expected_score| {
+		let mut message = Xcm::<()>(message);
+		let mut properties = props(Weight::from_parts(10, 10));
+		assert_eq!(
+			Barrier::should_execute(
+				&origin,
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut properties,
+			),
+			Ok(())
+		);
+		assert_eq!(properties.risk_score, expected_score);
+	};
+	let transact = Transact {
+		origin_kind: OriginKind::Native,
+		fallback_max_weight: None,
+		call: Vec::new().into(),
+	};
+	let beneficiary: Location = AccountId32 { network: None, id: [1; 32] }.into();
+	let foreign: Location = (Parent, Parent, GlobalConsensus(Kusama)).into();
+
+	// benign
+	assert_score(Parent.into(), vec![ClearOrigin], 0);
+	assert_score(
+		Parent.into(),
+		vec![DepositAsset { assets: Definite((Here, 1).into()), beneficiary: beneficiary.clone() }],
+		0,
+	);
+	// single factors
+	assert_score(Parent.into(), vec![transact.clone()], 50);
+	assert_score(
+		Parent.into(),
+		vec![SetAppendix(Xcm(vec![DepositAsset {
+			assets: All.into(),
+			beneficiary: beneficiary.clone(),
+		}]))],
+		30,
+	);
+	assert_score(foreign.clone(), vec![ClearOrigin], 20);
+	// combined factors
+	assert_score(foreign, vec![transact, DepositAsset { assets: All.into(), beneficiary }], 100);
+
+	// the score alone does not authorize execution
+	let mut message = Xcm::<()>(vec![ClearOrigin]);
+	assert_eq!(
+		ScoreRisk::<CompositionRisk<IsInVec<ForeignOrigins>>>::should_execute(
+			&Parent.into(),
+			message.inner_mut(),
+			Weight::from_parts(10, 10),
+			&mut props(Weight::zero()),
+		),
+		Err(ProcessMessageError::Unsupported)
+	);
+}
+
+#[test]
+fn require_clear_origin_before_transact_should_work() {
+	parameter_types! {
+		pub BridgedOrigins: Vec<Location> = vec![(Parent, Parent, GlobalConsensus(Kusama)).into()];
+	}
+	let assert_transact = |origin: Location, message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			RequireClearOriginBeforeTransact::<IsInVec<BridgedOrigins>>::deny_execution(
+				&origin,
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+	let transact = || Transact {
+		origin_kind: OriginKind::Native,
+		fallback_max_weight: None,
+		call: Vec::new().into(),
+	};
+	let bridged: Location = (Parent, Parent, GlobalConsensus(Kusama)).into();
+
+	// cleared bridged transact
+	assert_transact(bridged.clone(), vec![ClearOrigin, transact()], Ok(()));
+	// uncleared bridged transact
+	assert_transact(bridged.clone(), vec![transact()], Err(ProcessMessageError::Unsupported));
+	assert_transact(
+		bridged.clone(),
+		vec![transact(), ClearOrigin],
+		Err(ProcessMessageError::Unsupported),
+	);
+	assert_transact(bridged, vec![ClearOrigin], Ok(()));
+	// non-bridged transact
+	assert_transact(Parent.into(), vec![transact()], Ok(()));
+}
+
+#[test]
+fn feature_gated_should_work() {
+	parameter_types! {
+		pub static StrictMode: bool = false;
+	}
+	type Barrier = FeatureGated<StrictMode, AllowAll>;
+
+	// disabled: passes through to the next barrier
+	assert_barrier!(Barrier, Parent, [ClearOrigin], expects Err(Unsupported));
+	// enabled: delegates to the inner barrier
+	StrictMode::set(true);
+	assert_barrier!(Barrier, Parent, [ClearOrigin], expects Ok);
+	assert_barrier!(FeatureGated<StrictMode, DenyAll>, Parent, [ClearOrigin], expects Err(Unsupported));
+	// disabled again
+	StrictMode::set(false);
+	assert_barrier!(Barrier, Parent, [ClearOrigin], expects Err(Unsupported));
+}
+
+#[test]
+fn deny_unknown_networks_should_work() {
+	parameter_types! {
+		pub KnownNetworks: Vec<NetworkId> = vec![Polkadot, Kusama];
+	}
+	let assert_networks = |message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			DenyUnknownNetworks::<IsInVec<KnownNetworks>>::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+	let on = |network| Location::new(2, [GlobalConsensus(network), Parachain(1000)]);
+	let fees: Asset = (on(Kusama), 100).into();
+
+	// known networks only
+	assert_networks(vec![ClearOrigin], Ok(()));
+	assert_networks(
+		vec![
+			UniversalOrigin(GlobalConsensus(Kusama)),
+			WithdrawAsset(fees.clone().into()),
+			DepositAsset { assets: All.into(), beneficiary: on(Polkadot) },
+			ExportMessage { network: Kusama, destination: Here, xcm: Xcm(vec![]) },
+		],
+		Ok(()),
+	);
+	// unknown network in an asset id, a location, the origin or an export
+	let unknown: Asset = (on(Ethereum { chain_id: 1 }), 100).into();
+	assert_networks(vec![WithdrawAsset(unknown.into())], Err(ProcessMessageError::Unsupported));
+	assert_networks(
+		vec![DepositAsset { assets: All.into(), beneficiary: on(ByGenesis([1; 32])) }],
+		Err(ProcessMessageError::Unsupported),
+	);
+	assert_networks(
+		vec![UniversalOrigin(GlobalConsensus(BitcoinCore))],
+		Err(ProcessMessageError::Unsupported),
+	);
+	assert_networks(
+		vec![ExportMessage { network: BitcoinCore, destination: Here, xcm: Xcm(vec![]) }],
+		Err(ProcessMessageError::Unsupported),
+	);
+	// unknown network within nested programs
+	assert_networks(
+		vec![SetAppendix(Xcm(vec![ReportError(QueryResponseInfo {
+			destination: on(BitcoinCore),
+			query_id: 1,
+			max_weight: Weight::zero(),
+		})]))],
+		Err(ProcessMessageError::Unsupported),
+	);
+	assert_networks(
+		vec![InitiateTeleport {
+			assets: All.into(),
+			dest: Parent.into(),
+			xcm: Xcm(vec![DepositAsset { assets: All.into(), beneficiary: on(BitcoinCore) }]),
+		}],
+		Err(ProcessMessageError::Unsupported),
+	);
+}
+
+#[test]
+fn wrapping_barriers_should_forward_barrier_weight() {
+	struct Costly;
+	impl ShouldExecute for Costly {
+		fn should_execute<Call>(
+			_origin: &Location,
+			_instructions: &mut [Instruction<Call>],
+			_max_weight: Weight,
+			_properties: &mut Properties,
+		) -> Result<(), ProcessMessageError> {
+			Ok(())
+		}
+
+		fn barrier_weight<Call>(instructions: &[Instruction<Call>]) -> Weight {
+			Weight::from_parts(instructions.len() as u64, 1)
+		}
+	}
+	parameter_types! {
+		pub UniversalLocation: InteriorLocation = [GlobalConsensus(Polkadot), Parachain(1000)].into();
+	}
+	type Barrier = TrailingSetTopicAsId<
+		DenyThenTry<
+			DenyTrap,
+			WithComputedOrigin<(TakeWeightCredit, Costly), UniversalLocation, ConstU32<8>>,
+		>,
+	>;
+
+	let instructions: Vec<Instruction<()>> = vec![ClearOrigin, ClearOrigin, SetTopic([1; 32])];
+	assert_eq!(Barrier::barrier_weight(&instructions), Weight::from_parts(3, 1));
+	assert_eq!(<(Barrier, Costly)>::barrier_weight(&instructions), Weight::from_parts(6, 2));
+
+	use frame_support::traits::{ConstBool, Everything, Nothing};
+	struct Anything;
+	impl MessagePredicate for Anything {
+		fn matches<Call>(_origin: &Location, _instructions: &[Instruction<Call>]) -> bool {
+			true
+		}
+	}
+	parameter_types! {
+		pub const MaxFactor: u64 = 2;
+		pub const Remaining: Weight = Weight::MAX;
+	}
+	barrier_builder! {
+		pub struct BuiltBarrier {
+			"credit" => TakeWeightCredit,
+			"costly" => Costly,
+		}
+	}
+	type TestWeigher = FixedWeightBounds<UnitWeightCost, TestCall, MaxInstructions>;
+	let single = Weight::from_parts(3, 1);
+	assert_eq!(TracingShouldExecute::<Costly>::barrier_weight(&instructions), single);
+	assert_eq!(RestrictDescend::<Everything, Costly>::barrier_weight(&instructions), single);
+	assert_eq!(When::<Anything, Costly>::barrier_weight(&instructions), single);
+	assert_eq!(
+		WeightSanity::<TestWeigher, TestCall, MaxFactor, Costly>::barrier_weight(&instructions),
+		single
+	);
+	assert_eq!(TransactWithinBudget::<Remaining, Costly>::barrier_weight(&instructions), single);
+	assert_eq!(FeatureGated::<ConstBool<true>, Costly>::barrier_weight(&instructions), single);
+	assert_eq!(BuiltBarrier::barrier_weight(&instructions), single);
+	assert_eq!(
+		crate::All::<(Costly, TakeWeightCredit, Costly)>::barrier_weight(&instructions),
+		single * 2
+	);
+	// the more expensive of both branches
+	assert_eq!(OverrideFor::<Nothing, (), Costly>::barrier_weight(&instructions), single);
+	assert_eq!(OverrideFor::<Everything, Costly, ()>::barrier_weight(&instructions), single);
+}
+
+#[test]
+fn deny_barrier_weight_should_be_charged() {
+	struct Costly;
+	impl DenyExecution for Costly {
+		fn deny_execution<Call>(
+			_origin: &Location,
+			_instructions: &mut [Instruction<Call>],
+			_max_weight: Weight,
+			_properties: &mut Properties,
+		) -> Result<(), ProcessMessageError> {
+			Ok(())
+		}
+
+		fn barrier_weight<Call>(instructions: &[Instruction<Call>]) -> Weight {
+			Weight::from_parts(instructions.len() as u64, 1)
+		}
+	}
+
+	let instructions: Vec<Instruction<()>> =
+		vec![ClearOrigin, SetAppendix(Xcm(vec![ClearOrigin, ClearOrigin]))];
+	assert_eq!(
+		<(Costly, DenyTrap, Costly)>::barrier_weight(&instructions),
+		Weight::from_parts(4, 2)
+	);
+	#[derive(DenyExecution)]
+	#[allow(dead_code)]
+	struct Derived(Costly, DenyTrap, Costly);
+	assert_eq!(Derived::barrier_weight(&instructions), Weight::from_parts(4, 2));
+	// the deny barriers are charged on top of the allow barrier
+	assert_eq!(
+		DenyThenTry::<(Costly, DenyTrap), TakeWeightCredit>::barrier_weight(&instructions),
+		Weight::from_parts(2, 1)
+	);
+	// nested programs are charged for each program the inner barrier evaluates
+	let nested: Vec<Instruction<()>> = vec![
+		ClearOrigin,
+		DepositReserveAsset {
+			assets: Wild(All),
+			dest: Parent.into(),
+			xcm: Xcm(vec![ClearOrigin, ClearOrigin]),
+		},
+	];
+	assert_eq!(
+		CheckNestedProgram::<Costly, ConstU32<8>>::barrier_weight(&nested),
+		Weight::from_parts(2, 1)
+	);
+}
+
+#[test]
+fn barrier_weight_should_be_charged_by_executor() {
+	struct Costly;
+	impl ShouldExecute for Costly {
+		fn should_execute<Call>(
+			origin: &Location,
+			instructions: &mut [Instruction<Call>],
+			max_weight: Weight,
+			properties: &mut Properties,
+		) -> Result<(), ProcessMessageError> {
+			TestBarrier::should_execute(origin, instructions, max_weight, properties)
+		}
+
+		fn barrier_weight<Call>(_instructions: &[Instruction<Call>]) -> Weight {
+			Weight::from_parts(5, 5)
+		}
+	}
+	let execute = |origin: Location, weight_limit: Weight| {
+		let message = Xcm::<TestCall>(vec![ClearOrigin]);
+		XcmExecutor::<BarrierTestConfig<Costly>>::prepare_and_execute(
+			origin,
+			message.clone(),
+			&mut fake_message_hash(&message),
+			weight_limit,
+			Weight::zero(),
+		)
+	};
+	AllowUnpaidFrom::set(vec![Parent.into()]);
+
+	// the weight limit must cover the barrier on top of the message
+	assert_eq!(
+		execute(Parent.into(), Weight::from_parts(10, 10)),
+		Outcome::Error { error: XcmError::WeightLimitReached(Weight::from_parts(15, 15)) }
+	);
+	assert_eq!(
+		execute(Parent.into(), Weight::from_parts(15, 15)),
+		Outcome::Complete { used: Weight::from_parts(15, 15) }
+	);
+	// a rejected message is reported as a barrier error
+	assert_eq!(
+		execute(Parachain(1).into(), Weight::from_parts(15, 15)),
+		Outcome::Error { error: XcmError::Barrier }
+	);
+}
+
+#[test]
+fn restrict_report_dest_should_work() {
+	parameter_types! {
+		pub TrustedReporters: Vec<Location> = vec![(Parent, Parachain(1000)).into()];
+	}
+	let assert_report = |message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			RestrictReportDest::<IsInVec<TrustedReporters>>::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+	let info = |destination: Location| QueryResponseInfo {
+		destination,
+		query_id: 1,
+		max_weight: Weight::zero(),
+	};
+
+	// allowed report destinations
+	assert_report(vec![ReportError(info(Parent.into()))], Ok(()));
+	assert_report(
+		vec![ReportHolding {
+			response_info: info((Parent, Parachain(1000)).into()),
+			assets: All.into(),
+		}],
+		Ok(()),
+	);
+	assert_report(vec![ReportTransactStatus(info(Parent.into()))], Ok(()));
+	// disallowed report destinations
+	assert_report(
+		vec![ReportHolding {
+			response_info: info((Parent, Parachain(2000)).into()),
+			assets: All.into(),
+		}],
+		Err(ProcessMessageError::Unsupported),
+	);
+	assert_report(
+		vec![SetErrorHandler(Xcm(vec![ReportError(info(Here.into()))]))],
+		Err(ProcessMessageError::Unsupported),
+	);
+	assert_report(
+		vec![ReportTransactStatus(info((Parent, Parachain(2000)).into()))],
+		Err(ProcessMessageError::Unsupported),
+	);
+}
+
+#[test]
+fn suspend_on_queue_depth_should_work() {
+	parameter_types! {
+		pub static QueueDepth: u32 = 0;
+		pub const MaxQueueDepth: u32 = 5;
+	}
+	let is_suspended = |depth| {
+		QueueDepth::set(depth);
+		SuspendOnQueueDepth::<QueueDepth, MaxQueueDepth>::is_suspended(
+			&Parent.into(),
+			Xcm::<()>(vec![ClearOrigin]).inner_mut(),
+			Weight::from_parts(10, 10),
+			&mut props(Weight::zero()),
+		)
+	};
+
+	// below threshold
+	assert!(!is_suspended(0));
+	assert!(!is_suspended(4));
+	// at threshold
+	assert!(!is_suspended(5));
+	// above threshold
+	assert!(is_suspended(6));
+	assert!(is_suspended(u32::MAX));
+}
+
+#[test]
+fn deny_full_drain_to_foreign_should_work() {
+	struct IsForeign;
+	impl Contains<Location> for IsForeign {
+		fn contains(location: &Location) -> bool {
+			location.parents > 0
+		}
+	}
+	let assert_drain = |message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			DenyFullDrainToForeign::<IsForeign>::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+	let withdraw = WithdrawAsset((Here, 100).into());
+	let local: Location = AccountId32 { network: None, id: [1; 32] }.into();
+	let foreign: Location =
+		(Parent, Parachain(2000), AccountId32 { network: None, id: [1; 32] }).into();
+
+	// local beneficiary
+	assert_drain(
+		vec![withdraw.clone(), DepositAsset { assets: All.into(), beneficiary: local }],
+		Ok(()),
+	);
+	// foreign beneficiary
+	assert_drain(
+		vec![withdraw.clone(), DepositAsset { assets: All.into(), beneficiary: foreign.clone() }],
+		Err(ProcessMessageError::Unsupported),
+	);
+	assert_drain(
+		vec![
+			withdraw.clone(),
+			ClearOrigin,
+			DepositAsset { assets: Wild(AllCounted(1)), beneficiary: foreign.clone() },
+		],
+		Err(ProcessMessageError::Unsupported),
+	);
+	// partial deposit to a foreign beneficiary
+	assert_drain(
+		vec![
+			withdraw,
+			DepositAsset { assets: Definite((Here, 10).into()), beneficiary: foreign.clone() },
+		],
+		Ok(()),
+	);
+	// no withdrawal
+	assert_drain(
+		vec![
+			ReserveAssetDeposited((Parent, 100).into()),
+			DepositAsset { assets: All.into(), beneficiary: foreign },
+		],
+		Ok(()),
+	);
+}
+
+#[test]
+fn allow_until_should_work() {
+	parameter_types! {
+		pub TrustedOrigins: Vec<Location> = vec![Parachain(1).into()];
+		pub const Deadline: u32 = 100;
+		pub static Now: u32 = 0;
+	}
+	type Barrier = AllowUntil<IsInVec<TrustedOrigins>, Deadline, Now>;
+
+	// before the deadline
+	Now::set(99);
+	assert_barrier!(Barrier, Parachain(1), [ClearOrigin], expects Ok);
+	assert_barrier!(Barrier, Parachain(2), [ClearOrigin], expects Err(Unsupported));
+	// at and after the deadline
+	Now::set(100);
+	assert_barrier!(Barrier, Parachain(1), [ClearOrigin], expects Err(Unsupported));
+	Now::set(101);
+	assert_barrier!(Barrier, Parachain(1), [ClearOrigin], expects Err(Unsupported));
+}
+
+#[test]
+fn restrict_fee_asset_should_work() {
+	parameter_types! {
+		pub FeeAssets: Vec<AssetId> = vec![AssetId(Here.into()), AssetId(Parent.into())];
+	}
+	let assert_fees = |fees: Asset, expected_result| {
+		for message in [
+			vec![
+				WithdrawAsset(fees.clone().into()),
+				BuyExecution { fees: fees.clone(), weight_limit: Unlimited },
+			],
+			vec![WithdrawAsset(fees.clone().into()), PayFees { asset: fees.clone() }],
+		] {
+			let mut message = Xcm::<()>(message);
+			assert_eq!(
+				RestrictFeeAsset::<IsInVec<FeeAssets>>::deny_execution(
+					&Parent.into(),
+					message.inner_mut(),
+					Weight::from_parts(10, 10),
+					&mut props(Weight::zero()),
+				),
+				expected_result
+			);
+		}
+	};
+
+	// allowed fee assets
+	assert_fees((Here, 100).into(), Ok(()));
+	assert_fees((Parent, 100).into(), Ok(()));
+	// disallowed fee asset
+	assert_fees(
+		((Parent, Parachain(1000), GeneralIndex(1)), 100).into(),
+		Err(ProcessMessageError::Unsupported),
+	);
+}
+
+#[test]
+fn deny_here_origin_actions_should_work() {
+	struct TransactAction;
+	impl MatchAction for TransactAction {
+		fn is_action<Call>(instruction: &Instruction<Call>) -> bool {
+			matches!(instruction, Transact { .. })
+		}
+	}
+
+	let transact = || Transact {
+		origin_kind: OriginKind::Native,
+		fallback_max_weight: None,
+		call: vec![].into(),
+	};
+	let assert_here = |origin: Location, message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			DenyHereOriginActions::<TransactAction>::deny_execution(
+				&origin,
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+
+	// `Here` origin with the matched action
+	assert_here(Here.into(), vec![ClearOrigin, transact()], Err(ProcessMessageError::Unsupported));
+	// `Here` origin without the matched action
+	assert_here(Here.into(), vec![ClearOrigin], Ok(()));
+	// `Here` origin with the matched action in a nested program executed locally
+	assert_here(
+		Here.into(),
+		vec![SetErrorHandler(Xcm(vec![transact()]))],
+		Err(ProcessMessageError::Unsupported),
+	);
+	assert_here(
+		Here.into(),
+		vec![SetAppendix(Xcm(vec![ClearOrigin, transact()]))],
+		Err(ProcessMessageError::Unsupported),
+	);
+	// non-`Here` origins with the matched action
+	assert_here(Parent.into(), vec![transact()], Ok(()));
+	assert_here(Parachain(1).into(), vec![transact()], Ok(()));
+}
+
+#[test]
+fn verify_signature_should_work() {
+	// Signatures are carried by a `SetTopic` and valid if they are the hash of the payload.
+	struct TopicHashVerifier;
+	impl SignatureVerifier for TopicHashVerifier {
+		fn signature<Call>(instruction: &Instruction<Call>) -> Option<Vec<u8>> {
+			match instruction {
+				SetTopic(topic) => Some(topic.to_vec()),
+				_ => None,
+			}
+		}
+		fn verify(signature: &[u8], payload: &[u8]) -> bool {
+			signature == sp_io::hashing::blake2_256(payload)
+		}
+	}
+
+	let assert_signature = |message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			VerifySignature::<TopicHashVerifier>::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+	let program = |amount: u128| -> Vec<Instruction<()>> {
+		vec![
+			WithdrawAsset((Here, amount).into()),
+			DepositAsset { assets: AllCounted(1).into(), beneficiary: Here.into() },
+		]
+	};
+	let sign = |program: &[Instruction<()>]| {
+		SetTopic(sp_io::hashing::blake2_256(&Xcm::<()>(program.to_vec()).encode()))
+	};
+
+	// no signature
+	assert_signature(program(100), Ok(()));
+	// valid signature, wherever it is placed
+	let signature = sign(&program(100));
+	assert_signature([program(100), vec![signature.clone()]].concat(), Ok(()));
+	assert_signature([vec![signature.clone()], program(100)].concat(), Ok(()));
+	// tampered program
+	assert_signature(
+		[program(200), vec![signature]].concat(),
+		Err(ProcessMessageError::Unsupported),
+	);
+	// tampered signature
+	assert_signature(
+		[program(100), vec![SetTopic([1; 32])]].concat(),
+		Err(ProcessMessageError::Unsupported),
+	);
+}
+
+#[test]
+fn require_weight_consistency_should_work() {
+	parameter_types! {
+		pub const Tolerance: Weight = Weight::from_parts(5, 5);
+	}
+	let assert_consistency = |weight_limit: WeightLimit, max_weight: Weight, expected_result| {
+		for mut message in [
+			Xcm::<()>(vec![
+				WithdrawAsset((Here, 100).into()),
+				BuyExecution { fees: (Here, 100).into(), weight_limit: weight_limit.clone() },
+			]),
+			Xcm::<()>(vec![UnpaidExecution {
+				weight_limit: weight_limit.clone(),
+				check_origin: None,
+			}]),
+		] {
+			assert_eq!(
+				RequireWeightConsistency::<Tolerance>::deny_execution(
+					&Parent.into(),
+					message.inner_mut(),
+					max_weight,
+					&mut props(Weight::zero()),
+				),
+				expected_result
+			);
+		}
+	};
+
+	// exact match
+	assert_consistency(Limited(Weight::from_parts(30, 30)), Weight::from_parts(30, 30), Ok(()));
+	// within tolerance, in either direction
+	assert_consistency(Limited(Weight::from_parts(35, 25)), Weight::from_parts(30, 30), Ok(()));
+	// beyond tolerance in any dimension
+	assert_consistency(
+		Limited(Weight::from_parts(36, 30)),
+		Weight::from_parts(30, 30),
+		Err(ProcessMessageError::BadFormat),
+	);
+	assert_consistency(
+		Limited(Weight::from_parts(30, 24)),
+		Weight::from_parts(30, 30),
+		Err(ProcessMessageError::BadFormat),
+	);
+	// unlimited weight limits are not checked
+	assert_consistency(Unlimited, Weight::from_parts(30, 30), Ok(()));
+}
+
+#[test]
+fn first_pass_wins_should_work() {
+	use core::any::type_name;
+
+	parameter_types! {
+		pub static Verdicts: Vec<(&'static str, Result<(), ProcessMessageError>)> = vec![];
+	}
+	struct RecordVerdicts;
+	impl VerdictRecorder for RecordVerdicts {
+		fn record(barrier: &'static str, verdict: Result<(), ProcessMessageError>) {
+			Verdicts::mutate(|verdicts| verdicts.push((barrier, verdict)));
+		}
+	}
+	struct Malformed;
+	impl ShouldExecute for Malformed {
+		fn should_execute<RuntimeCall>(
+			_origin: &Location,
+			_instructions: &mut [Instruction<RuntimeCall>],
+			_max_weight: Weight,
+			_properties: &mut Properties,
+		) -> Result<(), ProcessMessageError> {
+			Err(ProcessMessageError::BadFormat)
+		}
+	}
+
+	// the first pass wins, after recording every evaluated barrier
+	assert_barrier!(
+		FirstPassWins<(DenyAll, Malformed, AllowAll, DenyAll), RecordVerdicts>,
+		Parent,
+		[ClearOrigin],
+		expects Ok
+	);
+	assert_eq!(
+		Verdicts::take(),
+		vec![
+			(type_name::<DenyAll>(), Err(ProcessMessageError::Unsupported)),
+			(type_name::<Malformed>(), Err(ProcessMessageError::BadFormat)),
+			(type_name::<AllowAll>(), Ok(())),
+		]
+	);
+
+	// the shared error is returned if all barriers fail alike
+	assert_barrier!(
+		FirstPassWins<(Malformed, Malformed), RecordVerdicts>,
+		Parent,
+		[ClearOrigin],
+		expects Err(BadFormat)
+	);
+	assert_eq!(Verdicts::take().len(), 2);
+
+	// and `Unsupported` if they fail differently
+	assert_barrier!(
+		FirstPassWins<(Malformed, DenyAll), RecordVerdicts>,
+		Parent,
+		[ClearOrigin],
+		expects Err(Unsupported)
+	);
+	assert_eq!(
+		Verdicts::take(),
+		vec![
+			(type_name::<Malformed>(), Err(ProcessMessageError::BadFormat)),
+			(type_name::<DenyAll>(), Err(ProcessMessageError::Unsupported)),
+		]
+	);
+
+	// names and barrier weight are those of the inner barriers
+	struct Costly;
+	impl ShouldExecute for Costly {
+		fn should_execute<RuntimeCall>(
+			_origin: &Location,
+			_instructions: &mut [Instruction<RuntimeCall>],
+			_max_weight: Weight,
+			_properties: &mut Properties,
+		) -> Result<(), ProcessMessageError> {
+			Ok(())
+		}
+
+		fn barrier_weight<Call>(_instructions: &[Instruction<Call>]) -> Weight {
+			Weight::from_parts(2, 1)
+		}
+	}
+	type Barrier = FirstPassWins<(Costly, AllowAll, Costly), RecordVerdicts>;
+	let mut names = Vec::new();
+	Barrier::names(&mut names);
+	assert_eq!(names, vec![type_name::<Costly>(), type_name::<AllowAll>(), type_name::<Costly>()]);
+	assert_eq!(Barrier::barrier_weight::<()>(&[ClearOrigin]), Weight::from_parts(4, 2));
+}
+
+#[test]
+fn deny_maximal_exchange_from_should_work() {
+	parameter_types! {
+		pub UntrustedOrigins: Vec<Location> = vec![Parachain(1).into()];
+	}
+	let exchange =
+		|maximal| ExchangeAsset { give: Wild(AllCounted(1)), want: (Parent, 100).into(), maximal };
+	let assert_exchange = |origin: Location, message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			DenyMaximalExchangeFrom::<IsInVec<UntrustedOrigins>>::deny_execution(
+				&origin,
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+
+	// untrusted origin
+	assert_exchange(
+		Parachain(1).into(),
+		vec![exchange(true)],
+		Err(ProcessMessageError::Unsupported),
+	);
+	assert_exchange(
+		Parachain(1).into(),
+		vec![SetErrorHandler(Xcm(vec![exchange(true)]))],
+		Err(ProcessMessageError::Unsupported),
+	);
+	assert_exchange(Parachain(1).into(), vec![exchange(false)], Ok(()));
+	// trusted origin
+	assert_exchange(Parachain(2).into(), vec![exchange(true)], Ok(()));
+	assert_exchange(Parachain(2).into(), vec![exchange(false)], Ok(()));
+}
+
+#[test]
+fn weight_ceiling_per_origin_should_work() {
+	struct TestCeilings;
+	impl Convert<Location, Weight> for TestCeilings {
+		fn convert(origin: Location) -> Weight {
+			match origin.unpack() {
+				(1, []) => Weight::from_parts(100, 100),
+				_ => Weight::from_parts(10, 10),
+			}
+		}
+	}
+	type Barrier = WeightCeilingPerOrigin<TestCeilings, AllowAll>;
+
+	// configured ceiling
+	assert_barrier!(Barrier, Parent, [ClearOrigin], max_weight: Weight::from_parts(100, 100), expects Ok);
+	assert_barrier!(
+		Barrier,
+		Parent,
+		[ClearOrigin],
+		max_weight: Weight::from_parts(101, 100),
+		expects Err(Overweight(Weight::from_parts(101, 100)))
+	);
+	// default ceiling
+	assert_barrier!(Barrier, Parachain(1), [ClearOrigin], max_weight: Weight::from_parts(10, 10), expects Ok);
+	assert_barrier!(
+		Barrier,
+		Parachain(1),
+		[ClearOrigin],
+		max_weight: Weight::from_parts(10, 11),
+		expects Err(Overweight(Weight::from_parts(10, 11)))
+	);
+	// the inner barrier still applies
+	assert_barrier!(
+		WeightCeilingPerOrigin<TestCeilings, DenyAll>,
+		Parent,
+		[ClearOrigin],
+		max_weight: Weight::from_parts(10, 10),
+		expects Err(Unsupported)
+	);
+}
+
+#[test]
+fn deny_teleport_with_transact_should_work() {
+	let teleport = || ReceiveTeleportedAsset((Parent, 100).into());
+	let transact = || Transact {
+		origin_kind: OriginKind::Native,
+		fallback_max_weight: None,
+		call: vec![].into(),
+	};
+	let assert_program = |message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			DenyTeleportWithTransact::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+
+	// each alone
+	assert_program(vec![teleport(), ClearOrigin], Ok(()));
+	assert_program(vec![transact()], Ok(()));
+	// together, in any order or nesting
+	assert_program(vec![teleport(), transact()], Err(ProcessMessageError::Unsupported));
+	assert_program(vec![transact(), teleport()], Err(ProcessMessageError::Unsupported));
+	assert_program(
+		vec![teleport(), SetAppendix(Xcm(vec![transact()]))],
+		Err(ProcessMessageError::Unsupported),
+	);
+}
+
+#[test]
+fn exempt_from_suspension_should_work() {
+	parameter_types! {
+		pub Governance: Vec<Location> = vec![Parent.into()];
+	}
+	struct AlwaysSuspended;
+	impl CheckSuspension for AlwaysSuspended {
+		fn is_suspended<Call>(
+			_origin: &Location,
+			_instructions: &mut [Instruction<Call>],
+			_max_weight: Weight,
+			_properties: &mut Properties,
+		) -> bool {
+			true
+		}
+	}
+	let is_suspended = |origin: Location| {
+		ExemptFromSuspension::<IsInVec<Governance>, AlwaysSuspended>::is_suspended(
+			&origin,
+			Xcm::<()>(vec![ClearOrigin]).inner_mut(),
+			Weight::from_parts(10, 10),
+			&mut props(Weight::zero()),
+		)
+	};
+
+	// exempt origin
+	assert!(!is_suspended(Parent.into()));
+	// non-exempt origins
+	assert!(is_suspended(Parachain(1).into()));
+	assert!(is_suspended((Parent, Parachain(1)).into()));
+}
+
+#[test]
+fn check_nested_program_should_work() {
+	parameter_types! {
+		pub const MaxDepth: u32 = 2;
+	}
+	type Barrier = CheckNestedProgram<DenyTrap, MaxDepth>;
+
+	let transfer = |remote_xcm: Vec<Instruction<()>>| InitiateTransfer {
+		destination: (Parent, Parachain(1000)).into(),
+		remote_fees: None,
+		preserve_origin: false,
+		assets: Default::default(),
+		remote_xcm: Xcm(remote_xcm),
+	};
+	let assert_nested = |message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			Barrier::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+
+	// valid nested programs, up to the maximal depth
+	assert_nested(vec![transfer(vec![ClearOrigin])], Ok(()));
+	assert_nested(vec![transfer(vec![transfer(vec![ClearOrigin])])], Ok(()));
+	// the outer program is not checked
+	assert_nested(vec![Trap(1), transfer(vec![ClearOrigin])], Ok(()));
+	// failing nested programs, including within error handlers
+	assert_nested(vec![transfer(vec![Trap(1)])], Err(ProcessMessageError::BadFormat));
+	assert_nested(
+		vec![transfer(vec![transfer(vec![Trap(1)])])],
+		Err(ProcessMessageError::BadFormat),
+	);
+	assert_nested(
+		vec![SetErrorHandler(Xcm(vec![transfer(vec![Trap(1)])]))],
+		Err(ProcessMessageError::BadFormat),
+	);
+	// over-depth nesting
+	assert_nested(
+		vec![transfer(vec![transfer(vec![transfer(vec![ClearOrigin])])])],
+		Err(ProcessMessageError::StackLimitReached),
+	);
+}
+
+#[test]
+fn credit_only_for_system_should_work() {
+	parameter_types! {
+		pub SystemOrigins: Vec<Location> = vec![Here.into()];
+	}
+	let assert_credit = |origin: Location, expected_result, expected_credit| {
+		let mut properties = props(Weight::from_parts(10, 10));
+		assert_eq!(
+			CreditOnlyForSystem::<IsInVec<SystemOrigins>, TakeWeightCredit>::should_execute(
+				&origin,
+				Xcm::<()>(vec![ClearOrigin]).inner_mut(),
+				Weight::from_parts(4, 4),
+				&mut properties,
+			),
+			expected_result
+		);
+		assert_eq!(properties.weight_credit, expected_credit);
+	};
+
+	// system origin keeps its credit
+	assert_credit(Here.into(), Ok(()), Weight::from_parts(6, 6));
+	// other origins have their credit zeroed
+	assert_credit(
+		Parachain(1).into(),
+		Err(ProcessMessageError::Overweight(Weight::from_parts(4, 4))),
+		Weight::zero(),
+	);
+}
+
+#[test]
+fn deny_interleaved_origin_should_work() {
+	let assert_origin = |message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			DenyInterleavedOrigin::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+	let withdraw = || WithdrawAsset((Parent, 100).into());
+
+	// prefix-only origin alterations
+	assert_origin(vec![withdraw()], Ok(()));
+	assert_origin(vec![UniversalOrigin(GlobalConsensus(Polkadot)), withdraw()], Ok(()));
+	assert_origin(
+		vec![DescendOrigin(Parachain(1).into()), ClearOrigin, withdraw(), withdraw()],
+		Ok(()),
+	);
+	// interleaved origin alterations
+	assert_origin(vec![withdraw(), ClearOrigin], Err(ProcessMessageError::BadFormat));
+	assert_origin(
+		vec![ClearOrigin, withdraw(), DescendOrigin(Parachain(1).into()), withdraw()],
+		Err(ProcessMessageError::BadFormat),
+	);
+}
+
+#[test]
+fn weight_sanity_suggests_weight_on_rejection() {
+	parameter_types! {
+		pub const MaxFactor: u64 = 2;
+	}
+	type TestWeigher = FixedWeightBounds<UnitWeightCost, TestCall, MaxInstructions>;
+	let assert_suggestion = |max_weight, expected_result, expected_suggestion| {
+		let mut properties = props(Weight::zero());
+		assert_eq!(
+			WeightSanity::<TestWeigher, TestCall, MaxFactor, AllowAll>::should_execute(
+				&Parent.into(),
+				Xcm::<()>(vec![ClearOrigin, RefundSurplus]).inner_mut(),
+				max_weight,
+				&mut properties,
+			),
+			expected_result
+		);
+		assert_eq!(properties.suggested_weight, expected_suggestion);
+	};
+
+	// accepted weights get no suggestion
+	assert_suggestion(Weight::from_parts(40, 40), Ok(()), None);
+	// rejected weights get the estimated weight suggested
+	assert_suggestion(
+		Weight::from_parts(41, 40),
+		Err(ProcessMessageError::Overweight(Weight::from_parts(41, 40))),
+		Some(Weight::from_parts(20, 20)),
+	);
+}
+
+#[test]
+fn instruction_kill_switch_should_work() {
+	parameter_types! {
+		pub static DisabledOpcodes: Vec<u8> = vec![];
+	}
+	let transact = || Transact {
+		origin_kind: OriginKind::Native,
+		fallback_max_weight: None,
+		call: vec![1, 2, 3].into(),
+	};
+	let opcode_of = |instruction: Instruction<()>| instruction.encode()[0];
+	let assert_program = |message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			InstructionKillSwitch::<IsInVec<DisabledOpcodes>>::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+
+	// nothing disabled
+	assert_program(vec![ClearOrigin, transact()], Ok(()));
+	// disabling `Transact`
+	DisabledOpcodes::set(vec![opcode_of(transact())]);
+	assert_program(vec![ClearOrigin, transact()], Err(ProcessMessageError::Unsupported));
+	assert_program(
+		vec![SetErrorHandler(Xcm(vec![transact()]))],
+		Err(ProcessMessageError::Unsupported),
+	);
+	assert_program(vec![ClearOrigin], Ok(()));
+	// disabling `ClearOrigin` as well
+	DisabledOpcodes::mutate(|disabled| disabled.push(opcode_of(ClearOrigin)));
+	assert_program(vec![ClearOrigin], Err(ProcessMessageError::Unsupported));
+	// re-enabling everything
+	DisabledOpcodes::set(vec![]);
+	assert_program(vec![ClearOrigin, transact()], Ok(()));
+}
+
+#[test]
+fn validate_topic_format_should_work() {
+	// Compliant topics start with a protocol tag and end with a zero byte.
+	struct TaggedTopic;
+	impl Contains<XcmHash> for TaggedTopic {
+		fn contains(topic: &XcmHash) -> bool {
+			topic.starts_with(b"xcm:") && topic[31] == 0
+		}
+	}
+	let assert_topic = |message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			ValidateTopicFormat::<TaggedTopic>::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+	let mut compliant = [0u8; 32];
+	compliant[..4].copy_from_slice(b"xcm:");
+	let mut untagged = compliant;
+	untagged[0] = b'X';
+	let mut unterminated = compliant;
+	unterminated[31] = 1;
+
+	// no trailing topic
+	assert_topic(vec![ClearOrigin], Ok(()));
+	assert_topic(vec![SetTopic(untagged), ClearOrigin], Ok(()));
+	// compliant topic
+	assert_topic(vec![ClearOrigin, SetTopic(compliant)], Ok(()));
+	// non-compliant topics
+	assert_topic(vec![ClearOrigin, SetTopic(untagged)], Err(ProcessMessageError::BadFormat));
+	assert_topic(vec![ClearOrigin, SetTopic(unterminated)], Err(ProcessMessageError::BadFormat));
+}
+
+#[test]
+fn max_messages_per_block_should_work() {
+	parameter_types! {
+		pub static CurrentBlock: u64 = 1;
+		pub static Counts: Vec<(Location, u64, u32)> = vec![];
+		pub const MaxPerBlock: u32 = 2;
+	}
+	struct TestStore;
+	impl MessageCountStore for TestStore {
+		type BlockNumber = u64;
+		fn current_block_number() -> u64 {
+			CurrentBlock::get()
+		}
+		fn count(origin: &Location) -> Option<(u64, u32)> {
+			Counts::get()
+				.into_iter()
+				.find_map(|(o, block, count)| (o == *origin).then_some((block, count)))
+		}
+		fn set_count(origin: &Location, block: u64, count: u32) {
+			Counts::mutate(|counts| {
+				counts.retain(|(o, ..)| o != origin);
+				counts.push((origin.clone(), block, count));
+			});
+		}
+	}
+	type Barrier = MaxMessagesPerBlock<AllowAll, TestStore, MaxPerBlock>;
+
+	// within the limit of the block
+	assert_barrier!(Barrier, Parachain(1), [ClearOrigin], expects Ok);
+	assert_barrier!(Barrier, Parachain(1), [ClearOrigin], expects Ok);
+	// past the limit of the block
+	assert_barrier!(Barrier, Parachain(1), [ClearOrigin], expects Err(Yield));
+	// other origins are counted separately
+	assert_barrier!(Barrier, Parachain(2), [ClearOrigin], expects Ok);
+	// the counts reset in the next block
+	CurrentBlock::set(2);
+	assert_barrier!(Barrier, Parachain(1), [ClearOrigin], expects Ok);
+	assert_barrier!(Barrier, Parachain(1), [ClearOrigin], expects Ok);
+	assert_barrier!(Barrier, Parachain(1), [ClearOrigin], expects Err(Yield));
+
+	// messages rejected by the inner barrier are not counted
+	assert_barrier!(
+		MaxMessagesPerBlock<DenyAll, TestStore, MaxPerBlock>,
+		Parachain(3),
+		[ClearOrigin],
+		expects Err(Unsupported)
+	);
+	assert_eq!(TestStore::count(&Parachain(3).into()), None);
+
+	// through the executor
+	let execute = |origin: Location| {
+		let message = Xcm::<TestCall>(vec![ClearOrigin]);
+		XcmExecutor::<BarrierTestConfig<MaxMessagesPerBlock<TestBarrier, TestStore, MaxPerBlock>>>::prepare_and_execute(
+			origin,
+			message.clone(),
+			&mut fake_message_hash(&message),
+			Weight::from_parts(10, 10),
+			Weight::zero(),
+		)
+	};
+	AllowUnpaidFrom::set(vec![Parachain(4).into()]);
+	CurrentBlock::set(3);
+	assert_eq!(execute(Parachain(5).into()), Outcome::Error { error: XcmError::Barrier });
+	assert_eq!(TestStore::count(&Parachain(5).into()), None);
+	for _ in 0..2 {
+		assert_eq!(
+			execute(Parachain(4).into()),
+			Outcome::Complete { used: Weight::from_parts(10, 10) }
+		);
+	}
+	assert_eq!(execute(Parachain(4).into()), Outcome::Error { error: XcmError::Barrier });
+}
+
+#[test]
+fn require_expect_transact_status_should_work() {
+	let transact = || Transact {
+		origin_kind: OriginKind::Native,
+		fallback_max_weight: None,
+		call: vec![].into(),
+	};
+	let expect = || ExpectTransactStatus(MaybeErrorCode::Success);
+	let assert_program = |message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			RequireExpectTransactStatus::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+
+	// no transact
+	assert_program(vec![ClearOrigin], Ok(()));
+	// transacts with expectations
+	assert_program(vec![transact(), expect()], Ok(()));
+	assert_program(vec![transact(), ClearOrigin, expect(), transact(), expect()], Ok(()));
+	// transacts without expectations
+	assert_program(vec![transact()], Err(ProcessMessageError::Unsupported));
+	assert_program(vec![expect(), transact()], Err(ProcessMessageError::Unsupported));
+	assert_program(vec![transact(), transact(), expect()], Err(ProcessMessageError::Unsupported));
+}
+
+#[test]
+fn max_beneficiary_depth_should_work() {
+	parameter_types! {
+		pub const MaxDepth: u8 = 2;
+	}
+	let assert_beneficiary = |beneficiary: Location, expected_result| {
+		for message in [
+			vec![
+				WithdrawAsset((Parent, 100).into()),
+				DepositAsset { assets: AllCounted(1).into(), beneficiary: beneficiary.clone() },
+			],
+			vec![TransferAsset { assets: (Parent, 100).into(), beneficiary: beneficiary.clone() }],
+			vec![SetErrorHandler(Xcm(vec![TransferAsset {
+				assets: (Parent, 100).into(),
+				beneficiary: beneficiary.clone(),
+			}]))],
+		] {
+			let mut message = Xcm::<()>(message);
+			assert_eq!(
+				MaxBeneficiaryDepth::<MaxDepth>::deny_execution(
+					&Parent.into(),
+					message.inner_mut(),
+					Weight::from_parts(10, 10),
+					&mut props(Weight::zero()),
+				),
+				expected_result
+			);
+		}
+	};
+
+	// shallow beneficiaries
+	assert_beneficiary(AccountIndex64 { network: None, index: 1 }.into(), Ok(()));
+	assert_beneficiary(
+		(Parent, Parachain(1), AccountIndex64 { network: None, index: 1 }).into(),
+		Ok(()),
+	);
+	// deep beneficiary
+	assert_beneficiary(
+		(Parachain(1), GeneralIndex(1), AccountIndex64 { network: None, index: 1 }).into(),
+		Err(ProcessMessageError::Unsupported),
+	);
+}
+
+#[test]
+fn catch_panics_should_work() {
+	struct Panicking;
+	impl ShouldExecute for Panicking {
+		fn should_execute<RuntimeCall>(
+			_origin: &Location,
+			_instructions: &mut [Instruction<RuntimeCall>],
+			_max_weight: Weight,
+			_properties: &mut Properties,
+		) -> Result<(), ProcessMessageError> {
+			panic!("buggy barrier");
+		}
+	}
+
+	// panics are converted into rejections
+	assert_barrier!(CatchPanics<Panicking>, Parent, [ClearOrigin], expects Err(Unsupported));
+	// so that the next barrier of a tuple is evaluated
+	assert_barrier!((CatchPanics<Panicking>, AllowAll), Parent, [ClearOrigin], expects Ok);
+	// verdicts of well-behaved barriers are kept
+	assert_barrier!(CatchPanics<AllowAll>, Parent, [ClearOrigin], expects Ok);
+	assert_barrier!(CatchPanics<DenyAll>, Parent, [ClearOrigin], expects Err(Unsupported));
+}
+
+#[test]
+fn require_universal_origin_from_should_work() {
+	parameter_types! {
+		pub BridgeOrigins: Vec<Location> = vec![(Parent, Parachain(1013)).into()];
+	}
+	let assert_header = |origin: Location, message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			RequireUniversalOriginFrom::<IsInVec<BridgeOrigins>>::deny_execution(
+				&origin,
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+	let bridge: Location = (Parent, Parachain(1013)).into();
+	let header = || UniversalOrigin(GlobalConsensus(Kusama));
+
+	// bridge messages with the header
+	assert_header(bridge.clone(), vec![header(), ClearOrigin], Ok(()));
+	// bridge messages without the header, or with a late one
+	assert_header(bridge.clone(), vec![ClearOrigin], Err(ProcessMessageError::Unsupported));
+	assert_header(
+		bridge.clone(),
+		vec![ClearOrigin, header()],
+		Err(ProcessMessageError::Unsupported),
+	);
+	assert_header(bridge, vec![], Err(ProcessMessageError::Unsupported));
+	// non-bridge messages
+	assert_header((Parent, Parachain(1000)).into(), vec![ClearOrigin], Ok(()));
+}
+
+#[test]
+fn allow_origin_instruction_should_work() {
+	fn opcode_of(instruction: Instruction<()>) -> u8 {
+		instruction.encode()[0]
+	}
+	// The relay chain may only start with `UnpaidExecution`, and siblings with `WithdrawAsset`.
+	struct TestPairs;
+	impl ContainsPair<Location, u8> for TestPairs {
+		fn contains(origin: &Location, opcode: &u8) -> bool {
+			let unpaid = opcode_of(UnpaidExecution { weight_limit: Unlimited, check_origin: None });
+			let withdraw = opcode_of(WithdrawAsset(Assets::new()));
+			match origin.unpack() {
+				(1, []) => *opcode == unpaid,
+				(1, [Parachain(_)]) => *opcode == withdraw,
+				_ => false,
+			}
+		}
+	}
+	let unpaid = || UnpaidExecution { weight_limit: Unlimited, check_origin: None };
+	let withdraw = || WithdrawAsset((Parent, 100).into());
+
+	// allowed pairs
+	assert_barrier!(AllowOriginInstruction<TestPairs>, Parent, [unpaid(), ClearOrigin], expects Ok);
+	assert_barrier!(
+		AllowOriginInstruction<TestPairs>,
+		(Parent, Parachain(1000)),
+		[withdraw(), ClearOrigin],
+		expects Ok
+	);
+	// disallowed pairs
+	assert_barrier!(
+		AllowOriginInstruction<TestPairs>,
+		Parent,
+		[withdraw(), unpaid()],
+		expects Err(Unsupported)
+	);
+	assert_barrier!(
+		AllowOriginInstruction<TestPairs>,
+		(Parent, Parachain(1000)),
+		[unpaid()],
+		expects Err(Unsupported)
+	);
+	assert_barrier!(AllowOriginInstruction<TestPairs>, Here, [unpaid()], expects Err(Unsupported));
+	// empty messages
+	assert_barrier!(AllowOriginInstruction<TestPairs>, Parent, [], expects Err(Unsupported));
+}
+
+#[test]
+fn deny_unbacked_refund_should_work() {
+	let fees: Asset = (Parent, 100).into();
+	let assert_refund = |message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			DenyUnbackedRefund::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+
+	// no refund
+	assert_refund(vec![ClearOrigin], Ok(()));
+	// refund with a prior purchase
+	assert_refund(
+		vec![
+			WithdrawAsset(fees.clone().into()),
+			BuyExecution { fees: fees.clone(), weight_limit: Unlimited },
+			RefundSurplus,
+		],
+		Ok(()),
+	);
+	assert_refund(
+		vec![WithdrawAsset(fees.clone().into()), PayFees { asset: fees.clone() }, RefundSurplus],
+		Ok(()),
+	);
+	// refund without a prior purchase
+	assert_refund(vec![RefundSurplus], Err(ProcessMessageError::BadFormat));
+	assert_refund(
+		vec![
+			WithdrawAsset(fees.clone().into()),
+			RefundSurplus,
+			BuyExecution { fees, weight_limit: Unlimited },
+		],
+		Err(ProcessMessageError::BadFormat),
+	);
+}
+
+#[test]
+fn require_canonical_assets_should_work() {
+	let assert_assets = |assets: Vec<Asset>, expected_result| {
+		let assets = Assets::from_sorted_and_deduplicated_skip_checks(assets);
+		for message in [
+			vec![WithdrawAsset(assets.clone()), ClearOrigin],
+			vec![ReserveAssetDeposited(assets.clone()), ClearOrigin],
+		] {
+			let mut message = Xcm::<()>(message);
+			assert_eq!(
+				RequireCanonicalAssets::deny_execution(
+					&Parent.into(),
+					message.inner_mut(),
+					Weight::from_parts(10, 10),
+					&mut props(Weight::zero()),
+				),
+				expected_result
+			);
+		}
+	};
+	let here: Asset = (Here, 100).into();
+	let parent: Asset = (Parent, 100).into();
+	assert!(here.id < parent.id);
+
+	// canonical asset lists
+	assert_assets(vec![], Ok(()));
+	assert_assets(vec![here.clone(), parent.clone()], Ok(()));
+	// unsorted asset list
+	assert_assets(vec![parent.clone(), here.clone()], Err(ProcessMessageError::BadFormat));
+	// duplicated asset list
+	assert_assets(vec![here.clone(), here, parent], Err(ProcessMessageError::BadFormat));
+}
+
+#[test]
+fn max_total_value_should_work() {
+	parameter_types! {
+		pub const ValueLimit: u128 = 1_000;
+	}
+	// The native asset is worth 1 and the relay chain asset 10; other assets have no price.
+	struct TestPricer;
+	impl AssetPricer for TestPricer {
+		fn price(id: &AssetId, amount: u128) -> Option<u128> {
+			match id.0.unpack() {
+				(0, []) => Some(amount),
+				(1, []) => amount.checked_mul(10),
+				_ => None,
+			}
+		}
+	}
+	let assert_value = |message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			MaxTotalValue::<ValueLimit, TestPricer>::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+	let beneficiary: Location = AccountIndex64 { network: None, index: 1 }.into();
+
+	// under the value limit across multiple assets and instructions
+	assert_value(
+		vec![
+			WithdrawAsset(vec![(Here, 500).into(), (Parent, 40).into()].into()),
+			TransferAsset { assets: (Parent, 10).into(), beneficiary: beneficiary.clone() },
+		],
+		Ok(()),
+	);
+	// deposits from the holding register are not counted again
+	assert_value(
+		vec![
+			WithdrawAsset((Here, 1_000).into()),
+			DepositAsset { assets: AllCounted(1).into(), beneficiary: beneficiary.clone() },
+		],
+		Ok(()),
+	);
+	// over the value limit across multiple assets and instructions
+	assert_value(
+		vec![
+			WithdrawAsset(vec![(Here, 500).into(), (Parent, 40).into()].into()),
+			TransferAsset { assets: (Parent, 11).into(), beneficiary: beneficiary.clone() },
+		],
+		Err(ProcessMessageError::Unsupported),
+	);
+	// assets without a price route
+	assert_value(
+		vec![WithdrawAsset(((Parent, Parachain(1000)), 1).into())],
+		Err(ProcessMessageError::Unsupported),
+	);
+}
+
+#[test]
+fn exactly_once_should_work() {
+	parameter_types! {
+		pub static SeenNonces: Vec<(Location, XcmHash)> = vec![];
+	}
+	struct TestNonceStore;
+	impl NonceStore for TestNonceStore {
+		fn is_seen(origin: &Location, nonce: &XcmHash) -> bool {
+			SeenNonces::get().iter().any(|(o, n)| o == origin && n == nonce)
+		}
+		fn note_seen(origin: &Location, nonce: XcmHash) {
+			SeenNonces::mutate(|seen| seen.push((origin.clone(), nonce)));
+		}
+	}
+
+	let assert_nonce = |origin: Location, message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			ExactlyOnce::<AllowAll, TestNonceStore>::should_execute(
+				&origin,
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+
+	// first seen, in any order
+	assert_nonce(Parent.into(), vec![ClearOrigin, SetTopic([2; 32])], Ok(()));
+	assert_nonce(Parent.into(), vec![ClearOrigin, SetTopic([1; 32])], Ok(()));
+	// replays
+	assert_nonce(
+		Parent.into(),
+		vec![ClearOrigin, SetTopic([2; 32])],
+		Err(ProcessMessageError::Unsupported),
+	);
+	assert_nonce(Parent.into(), vec![SetTopic([1; 32])], Err(ProcessMessageError::Unsupported));
+	// nonces are tracked per origin
+	assert_nonce(Parachain(1).into(), vec![ClearOrigin, SetTopic([2; 32])], Ok(()));
+	// missing topic
+	assert_nonce(Parent.into(), vec![ClearOrigin], Err(ProcessMessageError::BadFormat));
+	assert_nonce(
+		Parent.into(),
+		vec![SetTopic([3; 32]), ClearOrigin],
+		Err(ProcessMessageError::BadFormat),
+	);
+	assert_eq!(SeenNonces::get().len(), 3);
+
+	// rejected by the inner barrier, the nonce is not consumed
+	assert_barrier!(
+		ExactlyOnce<DenyAll, TestNonceStore>,
+		Parent,
+		[ClearOrigin, SetTopic([4; 32])],
+		expects Err(Unsupported)
+	);
+	assert!(!TestNonceStore::is_seen(&Parent.into(), &[4; 32]));
+	assert_nonce(Parent.into(), vec![ClearOrigin, SetTopic([4; 32])], Ok(()));
+
+	// through the executor, along with the allow side
+	let execute = |origin: Location, nonce: XcmHash| {
+		let message = Xcm::<TestCall>(vec![ClearOrigin, SetTopic(nonce)]);
+		XcmExecutor::<BarrierTestConfig<ExactlyOnce<TestBarrier, TestNonceStore>>>::prepare_and_execute(
+			origin,
+			message.clone(),
+			&mut fake_message_hash(&message),
+			Weight::from_parts(20, 20),
+			Weight::zero(),
+		)
+	};
+	AllowUnpaidFrom::set(vec![Parachain(1).into()]);
+	assert_eq!(execute(Parachain(2).into(), [5; 32]), Outcome::Error { error: XcmError::Barrier });
+	assert!(!TestNonceStore::is_seen(&Parachain(2).into(), &[5; 32]));
+	assert_eq!(
+		execute(Parachain(1).into(), [5; 32]),
+		Outcome::Complete { used: Weight::from_parts(20, 20) }
+	);
+	assert_eq!(execute(Parachain(1).into(), [5; 32]), Outcome::Error { error: XcmError::Barrier });
+}
+
+#[test]
+fn require_priceable_fee_should_work() {
+	// Only the relay chain asset has a price route.
+	struct TestRouter;
+	impl AssetPricer for TestRouter {
+		fn price(id: &AssetId, amount: u128) -> Option<u128> {
+			(id.0 == Location::parent()).then_some(amount)
+		}
+	}
+	let assert_fees = |fees: Asset, expected_result| {
+		for message in [
+			vec![
+				WithdrawAsset(fees.clone().into()),
+				BuyExecution { fees: fees.clone(), weight_limit: Unlimited },
+			],
+			vec![WithdrawAsset(fees.clone().into()), PayFees { asset: fees.clone() }],
+		] {
+			let mut message = Xcm::<()>(message);
+			assert_eq!(
+				RequirePriceableFee::<TestRouter>::deny_execution(
+					&Parent.into(),
+					message.inner_mut(),
+					Weight::from_parts(10, 10),
+					&mut props(Weight::zero()),
+				),
+				expected_result
+			);
+		}
+	};
+
+	// priceable fee asset
+	assert_fees((Parent, 100).into(), Ok(()));
+	// non-priceable fee assets
+	assert_fees(
+		((Parent, Parachain(1000), GeneralIndex(1)), 100).into(),
+		Err(ProcessMessageError::Unsupported),
+	);
+	assert_fees((Parent, [1u8; 4]).into(), Err(ProcessMessageError::Unsupported));
+}
+
+#[test]
+fn bucketed_should_work() {
+	parameter_types! {
+		pub const Split: sp_runtime::Percent = sp_runtime::Percent::from_percent(30);
+	}
+	// messages routed to `A` pass, those routed to `B` fail
+	let routed_to_b = |message_id: Option<XcmHash>| {
+		let mut properties = props(Weight::zero());
+		properties.message_id = message_id;
+		Bucketed::<AllowAll, DenyAll, Split>::should_execute(
+			&Parent.into(),
+			Xcm::<()>(vec![ClearOrigin]).inner_mut(),
+			Weight::from_parts(10, 10),
+			&mut properties,
+		)
+		.is_err()
+	};
+
+	// stable routing for a fixed id
+	let id = sp_io::hashing::blake2_256(b"fixed");
+	let route = routed_to_b(Some(id));
+	assert!((0..10).all(|_| routed_to_b(Some(id)) == route));
+	// messages without an id are routed to `A`
+	assert!(!routed_to_b(None));
+	// approximate split across many ids
+	let to_b = (0u32..1_000)
+		.filter(|i| routed_to_b(Some(sp_io::hashing::blake2_256(&i.encode()))))
+		.count();
+	assert!((250..350).contains(&to_b), "{to_b} of 1000 messages routed to B");
+}
+
+#[test]
+fn restrict_transact_pallet_should_work() {
+	parameter_types! {
+		pub AllowedPallets: Vec<u8> = vec![10, 42];
+	}
+	let transact = |call: Vec<u8>| Transact {
+		origin_kind: OriginKind::SovereignAccount,
+		fallback_max_weight: None,
+		call: call.into(),
+	};
+	let assert_transact = |message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			RestrictTransactPallet::<IsInVec<AllowedPallets>>::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+
+	// no transact
+	assert_transact(vec![ClearOrigin], Ok(()));
+	// allowed pallet indices, with arbitrary (undecodable) call data
+	assert_transact(vec![transact(vec![10, 0xff, 0xff]), transact(vec![42])], Ok(()));
+	// disallowed pallet indices
+	assert_transact(vec![transact(vec![11, 0])], Err(ProcessMessageError::Unsupported));
+	assert_transact(
+		vec![transact(vec![10, 0]), SetAppendix(Xcm(vec![transact(vec![0, 0])]))],
+		Err(ProcessMessageError::Unsupported),
+	);
+	// empty call
+	assert_transact(vec![transact(vec![])], Err(ProcessMessageError::BadFormat));
+}
+
+#[test]
+fn deny_over_deposit_should_work() {
+	let beneficiary: Location = AccountIndex64 { network: None, index: 1 }.into();
+	let deposit = |assets: AssetFilter| DepositAsset { assets, beneficiary: beneficiary.clone() };
+	let definite = |assets: Vec<Asset>| AssetFilter::Definite(assets.into());
+	let assert_deposit = |message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			DenyOverDeposit::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+
+	// balanced programs
+	assert_deposit(
+		vec![
+			WithdrawAsset((Parent, 100).into()),
+			ReceiveTeleportedAsset((Here, 50).into()),
+			deposit(definite(vec![(Parent, 60).into()])),
+			deposit(definite(vec![(Parent, 40).into(), (Here, 50).into()])),
+		],
+		Ok(()),
+	);
+	// over-deposits
+	assert_deposit(
+		vec![WithdrawAsset((Parent, 100).into()), deposit(definite(vec![(Parent, 101).into()]))],
+		Err(ProcessMessageError::BadFormat),
+	);
+	assert_deposit(
+		vec![
+			WithdrawAsset((Parent, 100).into()),
+			deposit(definite(vec![(Parent, 60).into()])),
+			deposit(definite(vec![(Parent, 60).into()])),
+		],
+		Err(ProcessMessageError::BadFormat),
+	);
+	assert_deposit(
+		vec![deposit(definite(vec![(Here, 1).into()]))],
+		Err(ProcessMessageError::BadFormat),
+	);
+	// wildcards take all matching assets
+	assert_deposit(vec![WithdrawAsset((Parent, 100).into()), deposit(Wild(AllCounted(1)))], Ok(()));
+	assert_deposit(
+		vec![
+			WithdrawAsset((Parent, 100).into()),
+			deposit(Wild(AllCounted(1))),
+			deposit(definite(vec![(Parent, 1).into()])),
+		],
+		Err(ProcessMessageError::BadFormat),
+	);
+	// amounts not known before execution stop the tracking
+	assert_deposit(
+		vec![
+			WithdrawAsset((Parent, 100).into()),
+			RefundSurplus,
+			deposit(definite(vec![(Parent, 200).into()])),
+		],
+		Ok(()),
+	);
+	// programs executed with another origin share the holding register
+	let descended = |xcm: Vec<Instruction<()>>| ExecuteWithOrigin {
+		descendant_origin: Some(AccountId32 { network: None, id: [1; 32] }.into()),
+		xcm: Xcm(xcm),
+	};
+	assert_deposit(
+		vec![
+			descended(vec![WithdrawAsset((Parent, 100).into())]),
+			deposit(definite(vec![(Parent, 100).into()])),
+		],
+		Ok(()),
+	);
+	assert_deposit(
+		vec![
+			WithdrawAsset((Parent, 100).into()),
+			descended(vec![deposit(definite(vec![(Parent, 60).into()]))]),
+			deposit(definite(vec![(Parent, 60).into()])),
+		],
+		Err(ProcessMessageError::BadFormat),
+	);
+	assert_deposit(
+		vec![
+			WithdrawAsset((Parent, 100).into()),
+			descended(vec![RefundSurplus]),
+			deposit(definite(vec![(Parent, 200).into()])),
+		],
+		Ok(()),
+	);
+}
+
+#[test]
+fn cooldown_should_work() {
+	parameter_types! {
+		pub static CurrentBlock: u64 = 10;
+		pub static LastMessages: Vec<(Location, u64)> = vec![];
+		pub const CooldownBlocks: u64 = 3;
+	}
+	struct TestStore;
+	impl LastMessageStore for TestStore {
+		type BlockNumber = u64;
+		fn current_block_number() -> u64 {
+			CurrentBlock::get()
+		}
+		fn last_message(origin: &Location) -> Option<u64> {
+			LastMessages::get()
+				.into_iter()
+				.find(|(o, _)| o == origin)
+				.map(|(_, block)| block)
+		}
+		fn note_message(origin: &Location, block: u64) {
+			LastMessages::mutate(|last| {
+				last.retain(|(o, _)| o != origin);
+				last.push((origin.clone(), block));
+			});
+		}
+	}
+	type Barrier = Cooldown<AllowAll, TestStore, CooldownBlocks>;
+
+	// first message
+	assert_barrier!(Barrier, Parachain(1), [ClearOrigin], expects Ok);
+	// within the cooldown
+	assert_barrier!(Barrier, Parachain(1), [ClearOrigin], expects Err(Yield));
+	CurrentBlock::set(12);
+	assert_barrier!(Barrier, Parachain(1), [ClearOrigin], expects Err(Yield));
+	// other origins have their own cooldown
+	assert_barrier!(Barrier, Parachain(2), [ClearOrigin], expects Ok);
+	// after the cooldown, which restarts
+	CurrentBlock::set(13);
+	assert_barrier!(Barrier, Parachain(1), [ClearOrigin], expects Ok);
+	assert_barrier!(Barrier, Parachain(1), [ClearOrigin], expects Err(Yield));
+	assert_eq!(TestStore::last_message(&Parachain(1).into()), Some(13));
+
+	// messages rejected by the inner barrier do not start the cooldown
+	assert_barrier!(
+		Cooldown<DenyAll, TestStore, CooldownBlocks>,
+		Parachain(3),
+		[ClearOrigin],
+		expects Err(Unsupported)
+	);
+	assert_eq!(TestStore::last_message(&Parachain(3).into()), None);
+
+	// through the executor
+	let execute = |origin: Location| {
+		let message = Xcm::<TestCall>(vec![ClearOrigin]);
+		XcmExecutor::<BarrierTestConfig<Cooldown<TestBarrier, TestStore, CooldownBlocks>>>::prepare_and_execute(
+			origin,
+			message.clone(),
+			&mut fake_message_hash(&message),
+			Weight::from_parts(10, 10),
+			Weight::zero(),
+		)
+	};
+	AllowUnpaidFrom::set(vec![Parachain(4).into()]);
+	assert_eq!(execute(Parachain(5).into()), Outcome::Error { error: XcmError::Barrier });
+	assert_eq!(TestStore::last_message(&Parachain(5).into()), None);
+	assert_eq!(
+		execute(Parachain(4).into()),
+		Outcome::Complete { used: Weight::from_parts(10, 10) }
+	);
+	assert_eq!(execute(Parachain(4).into()), Outcome::Error { error: XcmError::Barrier });
+	CurrentBlock::set(16);
+	assert_eq!(
+		execute(Parachain(4).into()),
+		Outcome::Complete { used: Weight::from_parts(10, 10) }
+	);
+}
+
+#[test]
+fn require_registered_assets_should_work() {
+	parameter_types! {
+		pub Registry: Vec<AssetId> = vec![AssetId(Here.into()), AssetId(Parent.into())];
+	}
+	let beneficiary: Location = AccountIndex64 { network: None, index: 1 }.into();
+	let assert_assets = |assets: Vec<Asset>, expected_result| {
+		for message in [
+			vec![WithdrawAsset(assets.clone().into())],
+			vec![TransferAsset { assets: assets.clone().into(), beneficiary: beneficiary.clone() }],
+		] {
+			let mut message = Xcm::<()>(message);
+			assert_eq!(
+				RequireRegisteredAssets::<IsInVec<Registry>>::deny_execution(
+					&Parent.into(),
+					message.inner_mut(),
+					Weight::from_parts(10, 10),
+					&mut props(Weight::zero()),
+				),
+				expected_result
+			);
+		}
+	};
+	let unregistered: Asset = ((Parent, Parachain(1000), GeneralIndex(1)), 100).into();
+
+	// registered assets only
+	assert_assets(vec![(Here, 100).into(), (Parent, 100).into()], Ok(()));
+	// unregistered assets present
+	assert_assets(vec![unregistered.clone()], Err(ProcessMessageError::Unsupported));
+	assert_assets(vec![(Parent, 100).into(), unregistered], Err(ProcessMessageError::Unsupported));
+}
+
+#[test]
+fn deny_weight_overflow_should_work() {
+	parameter_types! {
+		pub static Consumed: Weight = Weight::from_parts(10, 20);
+	}
+	type Barrier = DenyWeightOverflow<Consumed, AllowAll>;
+
+	// at the `u64` boundary of each dimension
+	let max = Weight::from_parts(u64::MAX - 10, u64::MAX - 20);
+	assert_barrier!(Barrier, Parent, [ClearOrigin], max_weight: max, expects Ok);
+	// just past the boundary of the `ref_time` dimension
+	let over = Weight::from_parts(u64::MAX - 9, 0);
+	assert_barrier!(Barrier, Parent, [ClearOrigin], max_weight: over, expects Err(Overweight(over)));
+	// just past the boundary of the `proof_size` dimension
+	let over = Weight::from_parts(0, u64::MAX - 19);
+	assert_barrier!(Barrier, Parent, [ClearOrigin], max_weight: over, expects Err(Overweight(over)));
+	// nothing consumed yet
+	Consumed::set(Weight::zero());
+	assert_barrier!(Barrier, Parent, [ClearOrigin], max_weight: Weight::MAX, expects Ok);
+	// the inner barrier still applies
+	assert_barrier!(
+		DenyWeightOverflow<Consumed, DenyAll>,
+		Parent,
+		[ClearOrigin],
+		max_weight: Weight::zero(),
+		expects Err(Unsupported)
+	);
+}
+
+#[test]
+fn require_protocol_version_should_work() {
+	parameter_types! {
+		pub const Min: u8 = 2;
+		pub const Max: u8 = 4;
+	}
+	let versioned = |version: u8| {
+		let mut topic = [7u8; 32];
+		topic[0] = version;
+		SetTopic(topic)
+	};
+	let assert_version = |message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			RequireProtocolVersion::<Min, Max>::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+
+	// in range, including both bounds
+	for version in 2..=4 {
+		assert_version(vec![versioned(version), ClearOrigin], Ok(()));
+	}
+	// below
+	assert_version(vec![versioned(1), ClearOrigin], Err(ProcessMessageError::Unsupported));
+	// above
+	assert_version(vec![versioned(5), ClearOrigin], Err(ProcessMessageError::Unsupported));
+	// only a leading topic carries the version
+	assert_version(vec![ClearOrigin, versioned(9)], Ok(()));
+	assert_version(vec![ClearOrigin], Ok(()));
+}
+
+#[test]
+fn deny_transact_in_appendix_should_work() {
+	let transact = || Transact {
+		origin_kind: OriginKind::Native,
+		fallback_max_weight: None,
+		call: vec![1, 2, 3].into(),
+	};
+	let assert_appendix = |message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			DenyTransactInAppendix::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+
+	// benign appendices
+	assert_appendix(vec![SetAppendix(Xcm(vec![RefundSurplus])), transact()], Ok(()));
+	assert_appendix(vec![SetErrorHandler(Xcm(vec![transact()]))], Ok(()));
+	// transact-containing appendices
+	assert_appendix(
+		vec![SetAppendix(Xcm(vec![RefundSurplus, transact()])), ClearOrigin],
+		Err(ProcessMessageError::Unsupported),
+	);
+	assert_appendix(
+		vec![SetAppendix(Xcm(vec![SetErrorHandler(Xcm(vec![transact()]))]))],
+		Err(ProcessMessageError::Unsupported),
+	);
+	// appendices set by nested programs
+	assert_appendix(
+		vec![SetErrorHandler(Xcm(vec![SetAppendix(Xcm(vec![transact()]))]))],
+		Err(ProcessMessageError::Unsupported),
+	);
+}
+
+#[test]
+fn restrict_asset_by_origin_should_work() {
+	// The relay chain may move its native asset only, and sibling 1000 also its own assets.
+	struct TestPairs;
+	impl ContainsPair<Location, AssetId> for TestPairs {
+		fn contains(origin: &Location, id: &AssetId) -> bool {
+			match origin.unpack() {
+				(1, []) => id.0 == Location::parent(),
+				(1, [Parachain(1000)]) =>
+					id.0 == Location::parent() ||
+						id.0.starts_with(&Location::new(1, [Parachain(1000)])),
+				_ => false,
+			}
+		}
+	}
+	let beneficiary: Location = AccountIndex64 { network: None, index: 1 }.into();
+	let assert_assets = |origin: Location, message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			RestrictAssetByOrigin::<TestPairs>::deny_execution(
+				&origin,
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+	let withdraw = |assets: Vec<Asset>| WithdrawAsset(assets.into());
+	let relay_asset: Asset = (Parent, 100).into();
+	let sibling_asset: Asset = ((Parent, Parachain(1000), GeneralIndex(1)), 100).into();
+	let sibling: Location = (Parent, Parachain(1000)).into();
+
+	// authorized pairs
+	assert_assets(Parent.into(), vec![withdraw(vec![relay_asset.clone()])], Ok(()));
+	assert_assets(
+		sibling.clone(),
+		vec![withdraw(vec![relay_asset.clone(), sibling_asset.clone()])],
+		Ok(()),
+	);
+	// unauthorized pairs, also among authorized assets
+	assert_assets(
+		Parent.into(),
+		vec![withdraw(vec![relay_asset.clone(), sibling_asset.clone()])],
+		Err(ProcessMessageError::Unsupported),
+	);
+	assert_assets(
+		Parent.into(),
+		vec![TransferAsset {
+			assets: sibling_asset.clone().into(),
+			beneficiary: beneficiary.clone(),
+		}],
+		Err(ProcessMessageError::Unsupported),
+	);
+	assert_assets(
+		(Parent, Parachain(2000)).into(),
+		vec![withdraw(vec![relay_asset.clone()])],
+		Err(ProcessMessageError::Unsupported),
+	);
+	// wildcards and nested programs are checked
+	assert_assets(
+		Parent.into(),
+		vec![DepositAsset {
+			assets: AllOf { id: sibling_asset.id.clone(), fun: WildFungible }.into(),
+			beneficiary: beneficiary.clone(),
+		}],
+		Err(ProcessMessageError::Unsupported),
+	);
+	assert_assets(
+		Parent.into(),
+		vec![SetErrorHandler(Xcm(vec![withdraw(vec![sibling_asset.clone()])]))],
+		Err(ProcessMessageError::Unsupported),
+	);
+	// fee payments do not move assets
+	assert_assets(Parent.into(), vec![PayFees { asset: sibling_asset }], Ok(()));
+}
+
+#[test]
+fn require_origin_assertions_should_work() {
+	parameter_types! {
+		pub const Count: u8 = 2;
+	}
+	// Programs containing a `Transact` are considered high-value.
+	struct ContainsTransact;
+	impl MessagePredicate for ContainsTransact {
+		fn matches<Call>(_origin: &Location, instructions: &[Instruction<Call>]) -> bool {
+			instructions.iter().any(|inst| matches!(inst, Transact { .. }))
+		}
+	}
+	let transact = || Transact {
+		origin_kind: OriginKind::Native,
+		fallback_max_weight: None,
+		call: vec![1, 2, 3].into(),
+	};
+	let expect_origin = || ExpectOrigin(Some(Parent.into()));
+	let assert_assertions = |message: Vec<Instruction<()>>, expected_result| {
+		let mut message = Xcm::<()>(message);
+		assert_eq!(
+			RequireOriginAssertions::<ContainsTransact, Count>::deny_execution(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+
+	// sufficient assertions
+	assert_assertions(vec![expect_origin(), expect_origin(), transact()], Ok(()));
+	assert_assertions(vec![expect_origin(), transact(), expect_origin(), expect_origin()], Ok(()));
+	// insufficient assertions
+	assert_assertions(vec![expect_origin(), transact()], Err(ProcessMessageError::Unsupported));
+	assert_assertions(vec![transact()], Err(ProcessMessageError::Unsupported));
+	// assertions in nested programs do not count
+	assert_assertions(
+		vec![expect_origin(), SetErrorHandler(Xcm(vec![expect_origin()])), transact()],
+		Err(ProcessMessageError::Unsupported),
+	);
+	// programs which are not high-value
+	assert_assertions(vec![ClearOrigin], Ok(()));
+}
+
+#[test]
+fn time_bounded_should_work() {
+	parameter_types! {
+		pub const Millis: u64 = 100;
+	}
+	struct Slow;
+	impl ShouldExecute for Slow {
+		fn should_execute<RuntimeCall>(
+			_origin: &Location,
+			_instructions: &mut [Instruction<RuntimeCall>],
+			_max_weight: Weight,
+			_properties: &mut Properties,
+		) -> Result<(), ProcessMessageError> {
+			std::thread::sleep(std::time::Duration::from_millis(1_000));
+			Ok(())
+		}
+	}
+	struct ReadsStorage;
+	impl ShouldExecute for ReadsStorage {
+		fn should_execute<RuntimeCall>(
+			_origin: &Location,
+			_instructions: &mut [Instruction<RuntimeCall>],
+			_max_weight: Weight,
+			_properties: &mut Properties,
+		) -> Result<(), ProcessMessageError> {
+			sp_io::storage::get(b"key");
+			Ok(())
+		}
+	}
+
+	// verdicts of fast barriers are kept
+	assert_barrier!(TimeBounded<AllowAll, Millis>, Parent, [ClearOrigin], expects Ok);
+	assert_barrier!(TimeBounded<DenyAll, Millis>, Parent, [ClearOrigin], expects Err(Unsupported));
+	// slow barriers are rejected once they overrun
+	assert_barrier!(TimeBounded<Slow, Millis>, Parent, [ClearOrigin], expects Err(Unsupported));
+	// the worker has no externalities, so accessing storage panics and rejects the message
+	sp_io::TestExternalities::default().execute_with(|| {
+		assert_barrier!(
+			TimeBounded<ReadsStorage, Millis>,
+			Parent,
+			[ClearOrigin],
+			expects Err(Unsupported)
+		);
+	});
+	// changes made to the properties are copied back
+	let mut message = Xcm::<()>(vec![ClearOrigin]);
+	let mut properties = props(Weight::from_parts(100, 100));
+	assert_eq!(
+		TimeBounded::<TakeWeightCredit, Millis>::should_execute(
+			&Parent.into(),
+			message.inner_mut(),
+			Weight::from_parts(10, 10),
+			&mut properties,
+		),
+		Ok(())
+	);
+	assert_eq!(properties.weight_credit, Weight::from_parts(90, 90));
+}
+
+#[test]
+fn cap_unpaid_weight_should_work() {
+	struct TestCaps;
+	impl Convert<Location, Weight> for TestCaps {
+		fn convert(origin: Location) -> Weight {
+			match origin.unpack() {
+				(1, []) => Weight::MAX,
+				(1, [Parachain(1000)]) => Weight::from_parts(100, 100),
+				_ => Weight::zero(),
+			}
+		}
+	}
+	let assert_unpaid = |origin: Location, weight_limit: WeightLimit, expected_result| {
+		let mut message =
+			Xcm::<()>(vec![UnpaidExecution { weight_limit, check_origin: None }, ClearOrigin]);
+		assert_eq!(
+			CapUnpaidWeight::<TestCaps>::deny_execution(
+				&origin,
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut props(Weight::zero()),
+			),
+			expected_result
+		);
+	};
+	let sibling: Location = (Parent, Parachain(1000)).into();
+	let other: Location = (Parent, Parachain(2000)).into();
+
+	// uncapped origin
+	assert_unpaid(Parent.into(), Unlimited, Ok(()));
+	assert_unpaid(Parent.into(), Limited(Weight::MAX), Ok(()));
+	// capped origin
+	assert_unpaid(sibling.clone(), Limited(Weight::from_parts(100, 100)), Ok(()));
+	assert_unpaid(
+		sibling.clone(),
+		Limited(Weight::from_parts(101, 100)),
+		Err(ProcessMessageError::Unsupported),
+	);
+	assert_unpaid(
+		sibling.clone(),
+		Limited(Weight::from_parts(100, 101)),
+		Err(ProcessMessageError::Unsupported),
+	);
+	assert_unpaid(sibling, Unlimited, Err(ProcessMessageError::Unsupported));
+	// default cap
+	assert_unpaid(other.clone(), Limited(Weight::zero()), Ok(()));
+	assert_unpaid(
+		other.clone(),
+		Limited(Weight::from_parts(1, 0)),
+		Err(ProcessMessageError::Unsupported),
+	);
+	assert_unpaid(other, Unlimited, Err(ProcessMessageError::Unsupported));
+}
+
+#[test]
+fn ensure_message_id_should_work() {
+	use sp_runtime::traits::{BlakeTwo256, Hash};
+	type Barrier = (EnsureMessageId<BlakeTwo256>, AllowAll);
+	let evaluate = |message: Vec<Instruction<()>>, message_id: Option<XcmHash>| {
+		let mut message = Xcm::<()>(message);
+		let mut properties = props(Weight::zero());
+		properties.message_id = message_id;
+		assert_eq!(
+			Barrier::should_execute(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut properties,
+			),
+			Ok(())
+		);
+		properties.message_id
+	};
+
+	// a present id is unchanged
+	assert_eq!(evaluate(vec![ClearOrigin], Some([1; 32])), Some([1; 32]));
+	// as is an absent id when there is a trailing topic
+	assert_eq!(evaluate(vec![ClearOrigin, SetTopic([2; 32])], None), None);
+	// an absent id is derived from the instructions
+	let message = vec![ClearOrigin, RefundSurplus];
+	let expected: XcmHash = <BlakeTwo256 as Hash>::hash(&message.encode()).into();
+	assert_eq!(evaluate(message.clone(), None), Some(expected));
+	assert_eq!(evaluate(message, None), Some(expected));
+	assert_ne!(evaluate(vec![ClearOrigin], None), Some(expected));
+	// the barrier passes on to the next barrier of the tuple
+	assert_barrier!(EnsureMessageId<BlakeTwo256>, Parent, [ClearOrigin], expects Err(Unsupported));
+}
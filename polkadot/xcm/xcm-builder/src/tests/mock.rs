@@ -45,7 +45,7 @@ pub use xcm_executor::{
 	traits::{
 		AssetExchange, AssetLock, CheckSuspension, ConvertOrigin, DenyExecution, Enact, ExportXcm,
 		FeeManager, FeeReason, LockError, OnResponse, Properties, QueryHandler,
-		QueryResponseStatus, TransactAsset,
+		QueryResponseStatus, ShouldExecute, TransactAsset,
 	},
 	AssetsInHolding, Config,
 };
@@ -733,8 +733,12 @@ impl Contains<Location> for ParentPrefix {
 	}
 }
 
-pub struct TestConfig;
-impl Config for TestConfig {
+pub type TestConfig =
+	BarrierTestConfig<TrailingSetTopicAsId<RespectSuspension<TestBarrier, TestSuspender>>>;
+
+/// The test configuration with a custom `Barrier`, for testing barriers through the executor.
+pub struct BarrierTestConfig<Barrier>(core::marker::PhantomData<Barrier>);
+impl<Barrier: ShouldExecute> Config for BarrierTestConfig<Barrier> {
 	type RuntimeCall = TestCall;
 	type XcmSender = TestMessageSender;
 	type AssetTransactor = TestAssetTransactor;
@@ -742,7 +746,7 @@ impl Config for TestConfig {
 	type IsReserve = TestIsReserve;
 	type IsTeleporter = TestIsTeleporter;
 	type UniversalLocation = ExecutorUniversalLocation;
-	type Barrier = TrailingSetTopicAsId<RespectSuspension<TestBarrier, TestSuspender>>;
+	type Barrier = Barrier;
 	type Weigher = FixedWeightBounds<UnitWeightCost, TestCall, MaxInstructions>;
 	type Trader = FixedRateOfFungible<WeightPrice, ()>;
 	type ResponseHandler = TestResponseHandler;
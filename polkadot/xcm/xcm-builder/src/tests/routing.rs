@@ -19,7 +19,7 @@ use frame_support::{assert_ok, traits::Everything};
 use xcm_executor::traits::Properties;
 
 fn props() -> Properties {
-	Properties { weight_credit: Weight::zero(), message_id: None }
+	Properties::new(Weight::zero(), None)
 }
 
 #[test]
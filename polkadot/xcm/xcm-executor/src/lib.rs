@@ -42,6 +42,16 @@ use traits::{
 
 pub use traits::RecordXcm;
 
+#[doc(hidden)]
+pub mod __private {
+	pub use alloc::vec::Vec;
+	pub use frame_support::{self, traits::ProcessMessageError};
+	pub use xcm::{
+		self,
+		latest::{Instruction, Location, Weight},
+	};
+}
+
 mod assets;
 pub use assets::AssetsInHolding;
 mod config;
@@ -202,17 +212,18 @@ impl<Config: config::Config> XcmExecutor<Config> {
 	}
 }
 
-pub struct WeighedMessage<Call>(Weight, Xcm<Call>);
+/// A message weighed by the executor, along with the weight of evaluating the barrier on it.
+pub struct WeighedMessage<Call>(Weight, Xcm<Call>, Weight);
 impl<C> PreparedMessage for WeighedMessage<C> {
 	fn weight_of(&self) -> Weight {
-		self.0
+		self.0.saturating_add(self.2)
 	}
 }
 
 #[cfg(any(test, feature = "std"))]
 impl<C> WeighedMessage<C> {
 	pub fn new(weight: Weight, message: Xcm<C>) -> Self {
-		Self(weight, message)
+		Self(weight, message, Weight::zero())
 	}
 }
 
@@ -222,13 +233,18 @@ impl<Config: config::Config> ExecuteXcm<Config::RuntimeCall> for XcmExecutor<Con
 		mut message: Xcm<Config::RuntimeCall>,
 	) -> Result<Self::Prepared, Xcm<Config::RuntimeCall>> {
 		match Config::Weigher::weight(&mut message) {
-			Ok(weight) => Ok(WeighedMessage(weight, message)),
+			Ok(weight) => {
+				let barrier_weight = Config::Barrier::barrier_weight(message.inner());
+				Ok(WeighedMessage(weight, message, barrier_weight))
+			},
 			Err(_) => Err(message),
 		}
 	}
 	fn execute(
 		origin: impl Into<Location>,
-		WeighedMessage(xcm_weight, mut message): WeighedMessage<Config::RuntimeCall>,
+		WeighedMessage(xcm_weight, mut message, barrier_weight): WeighedMessage<
+			Config::RuntimeCall,
+		>,
 		id: &mut XcmHash,
 		weight_credit: Weight,
 	) -> Outcome {
@@ -240,7 +256,7 @@ impl<Config: config::Config> ExecuteXcm<Config::RuntimeCall> for XcmExecutor<Con
 			?weight_credit,
 			"Executing message",
 		);
-		let mut properties = Properties { weight_credit, message_id: None };
+		let mut properties = Properties::new(weight_credit, None);
 
 		// We only want to record under certain conditions (mainly only during dry-running),
 		// so as to not degrade regular performance.
@@ -262,6 +278,10 @@ impl<Config: config::Config> ExecuteXcm<Config::RuntimeCall> for XcmExecutor<Con
 				error = ?e,
 				"Barrier blocked execution",
 			);
+			traits::report_barrier_rejection(
+				properties.last_rejection.unwrap_or(core::any::type_name::<Config::Barrier>()),
+				e,
+			);
 			return Outcome::Error { error: XcmError::Barrier }
 		}
 
@@ -283,7 +303,8 @@ impl<Config: config::Config> ExecuteXcm<Config::RuntimeCall> for XcmExecutor<Con
 			}
 		}
 
-		vm.post_process(xcm_weight)
+		// The weight of evaluating the barrier is consumed on top of that of the message.
+		vm.post_process(xcm_weight.saturating_add(barrier_weight))
 	}
 
 	fn charge_fees(origin: impl Into<Location>, fees: Assets) -> XcmResult {
@@ -42,7 +42,14 @@ pub use on_response::{OnResponse, QueryHandler, QueryResponseStatus, VersionChan
 mod process_transaction;
 pub use process_transaction::ProcessTransaction;
 mod should_execute;
-pub use should_execute::{CheckSuspension, DenyExecution, Properties, ShouldExecute};
+pub(crate) use should_execute::report_barrier_rejection;
+pub use should_execute::{
+	effective_origin, with_barrier_event_sink, Accepted, BarrierDecision, BarrierError,
+	BarrierEvent, CheckSuspension, DenyExecution, Properties, ShouldExecute, ShouldExecuteReport,
+};
+#[cfg(feature = "std")]
+pub use should_execute::{record_barrier_decision, trace_barrier_decisions};
+pub use xcm_procedural::{prioritized, DenyExecution, ShouldExecute};
 mod transact_asset;
 pub use transact_asset::TransactAsset;
 mod hrmp;
@@ -14,12 +14,17 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
+use alloc::vec::Vec;
 use core::result::Result;
-use frame_support::traits::ProcessMessageError;
-use xcm::latest::{Instruction, Location, Weight, XcmHash};
+use frame_support::traits::{Get, ProcessMessageError};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::traits::Saturating;
+use xcm::latest::{Instruction, InteriorLocation, Junctions, Location, Weight, XcmHash};
 
 /// Properties of an XCM message and its imminent execution.
 #[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Properties {
 	/// The amount of weight that the system has determined this
 	/// message may utilize in its execution. Typically non-zero only because of prior fee
@@ -28,6 +33,34 @@ pub struct Properties {
 	/// The identity of the message, if one is known. If left as `None`, then it will generally
 	/// default to the hash of the message which may be non-unique.
 	pub message_id: Option<XcmHash>,
+	/// The number of elements of `ShouldExecute` tuples evaluated so far, including the one which
+	/// passed, if any. Useful for spotting misordered tuples.
+	pub barriers_evaluated: u32,
+	/// The type name of the barrier which caused the last rejection by a barrier tuple, if any.
+	/// Cleared whenever a `ShouldExecute` tuple passes.
+	#[cfg_attr(feature = "serde", serde(skip))]
+	pub last_rejection: Option<&'static str>,
+	/// A score of the risk posed by the message, as computed by a risk-scoring barrier. Zero
+	/// unless computed, with higher values indicating riskier messages.
+	pub risk_score: u8,
+	/// A weight limit suggested to the sender by a barrier rejecting the message for its declared
+	/// weight, e.g. so that a `ReportError` path could relay it. `None` unless suggested.
+	pub suggested_weight: Option<Weight>,
+}
+
+impl Properties {
+	/// Returns the properties of a message with the given `weight_credit` and `message_id`, none of
+	/// the other properties being established yet.
+	pub fn new(weight_credit: Weight, message_id: Option<XcmHash>) -> Self {
+		Self {
+			weight_credit,
+			message_id,
+			barriers_evaluated: 0,
+			last_rejection: None,
+			risk_score: 0,
+			suggested_weight: None,
+		}
+	}
 }
 
 /// Trait to determine whether the execution engine should actually execute a given XCM.
@@ -48,6 +81,82 @@ pub trait ShouldExecute {
 		max_weight: Weight,
 		properties: &mut Properties,
 	) -> Result<(), ProcessMessageError>;
+
+	/// Appends the type names of the barriers making up this barrier to `out`.
+	///
+	/// Appends the type name of the barrier itself by default; tuples append the type name of each
+	/// of their elements, and wrappers the names of the barriers they wrap.
+	fn names(out: &mut Vec<&'static str>) {
+		out.push(core::any::type_name::<Self>());
+	}
+
+	/// Returns the weight of evaluating this barrier against `instructions`, which the executor
+	/// adds to the weight consumed by the message.
+	///
+	/// Zero by default; tuples sum the weights of all of their elements.
+	fn barrier_weight<RuntimeCall>(_instructions: &[Instruction<RuntimeCall>]) -> Weight {
+		Weight::zero()
+	}
+}
+
+/// The verdict of a single barrier, as recorded while tracing the evaluation of barrier tuples.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct BarrierDecision {
+	/// The type name of the evaluated barrier.
+	pub barrier: &'static str,
+	/// The result returned by the barrier.
+	pub result: Result<(), ProcessMessageError>,
+}
+
+#[cfg(feature = "std")]
+environmental::environmental!(barrier_decisions: Vec<BarrierDecision>);
+
+/// Records the verdict of `barrier`, if barrier decisions are currently being traced.
+///
+/// Called by barrier tuples for each of their elements, and usable by barrier wrappers to record
+/// their inner barrier.
+#[cfg(feature = "std")]
+pub fn record_barrier_decision(barrier: &'static str, result: Result<(), ProcessMessageError>) {
+	barrier_decisions::with(|decisions| decisions.push(BarrierDecision { barrier, result }));
+}
+
+/// Executes `f` and returns its result along with the verdicts of all barrier tuple elements
+/// evaluated meanwhile, in evaluation order.
+///
+/// This is meant for dry-running and tooling, hence only available with the `std` feature.
+#[cfg(feature = "std")]
+pub fn trace_barrier_decisions<R>(f: impl FnOnce() -> R) -> (R, Vec<BarrierDecision>) {
+	let mut decisions = Vec::new();
+	let result = barrier_decisions::using(&mut decisions, f);
+	(result, decisions)
+}
+
+/// A sink for barrier rejections, notified by the executor whenever its barrier rejects a message.
+pub trait BarrierEvent {
+	/// Called with the type name of the rejecting `barrier` and the `error` it returned.
+	fn on_rejection(&mut self, barrier: &'static str, error: ProcessMessageError);
+}
+
+pub(crate) use barrier_events::report_barrier_rejection;
+pub use barrier_events::with_barrier_event_sink;
+
+// Kept in a module of its own, as `environmental!` on a trait object defines a module-level static.
+mod barrier_events {
+	use super::BarrierEvent;
+	use frame_support::traits::ProcessMessageError;
+
+	environmental::environmental!(barrier_event_sink: trait BarrierEvent);
+
+	/// Notifies the barrier event sink currently installed, if any, of a rejection by `barrier`.
+	pub(crate) fn report_barrier_rejection(barrier: &'static str, error: ProcessMessageError) {
+		barrier_event_sink::with(|sink| sink.on_rejection(barrier, error));
+	}
+
+	/// Executes `f` with `sink` installed as the barrier event sink, such that it is notified of
+	/// every message rejected by the executor's barrier meanwhile.
+	pub fn with_barrier_event_sink<R>(sink: &mut dyn BarrierEvent, f: impl FnOnce() -> R) -> R {
+		barrier_event_sink::using(sink, f)
+	}
 }
 
 #[impl_trait_for_tuples::impl_for_tuples(30)]
@@ -60,8 +169,13 @@ impl ShouldExecute for Tuple {
 	) -> Result<(), ProcessMessageError> {
 		for_tuples!( #(
 			let barrier = core::any::type_name::<Tuple>();
- 			match Tuple::should_execute(origin, instructions, max_weight, properties) {
+			properties.barriers_evaluated.saturating_inc();
+			let result = Tuple::should_execute(origin, instructions, max_weight, properties);
+			#[cfg(feature = "std")]
+			record_barrier_decision(barrier, result);
+			match result {
 				Ok(()) => {
+					properties.last_rejection = None;
 					tracing::trace!(
 						target: "xcm::should_execute",
 						?origin,
@@ -74,6 +188,7 @@ impl ShouldExecute for Tuple {
 					return Ok(())
 				},
 				Err(error) => {
+					properties.last_rejection = Some(barrier);
 					tracing::trace!(
 						target: "xcm::should_execute",
 						?origin,
@@ -90,6 +205,113 @@ impl ShouldExecute for Tuple {
 
 		Err(ProcessMessageError::Unsupported)
 	}
+
+	fn names(out: &mut Vec<&'static str>) {
+		for_tuples!( #( out.push(core::any::type_name::<Tuple>()); )* );
+	}
+
+	fn barrier_weight<RuntimeCall>(instructions: &[Instruction<RuntimeCall>]) -> Weight {
+		let mut weight = Weight::zero();
+		for_tuples!( #( weight.saturating_accrue(Tuple::barrier_weight(instructions)); )* );
+		weight
+	}
+}
+
+/// Evaluates `B` up to `N` times in order, passing as soon as one evaluation passes.
+///
+/// Barriers are types rather than values, so all elements are the same barrier; this is useful when
+/// `B` depends on state which evolves between evaluations, e.g. `Properties`. An empty array never
+/// passes.
+impl<B: ShouldExecute, const N: usize> ShouldExecute for [B; N] {
+	fn should_execute<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		let barrier = core::any::type_name::<B>();
+		for _ in 0..N {
+			properties.barriers_evaluated.saturating_inc();
+			let result = B::should_execute(origin, instructions, max_weight, properties);
+			#[cfg(feature = "std")]
+			record_barrier_decision(barrier, result);
+			if result.is_err() {
+				properties.last_rejection = Some(barrier);
+				continue
+			}
+			properties.last_rejection = None;
+			tracing::trace!(
+				target: "xcm::should_execute",
+				?origin,
+				?instructions,
+				?max_weight,
+				?properties,
+				%barrier,
+				"pass barrier",
+			);
+			return Ok(())
+		}
+
+		Err(ProcessMessageError::Unsupported)
+	}
+
+	fn names(out: &mut Vec<&'static str>) {
+		(0..N).for_each(|_| out.push(core::any::type_name::<B>()));
+	}
+
+	fn barrier_weight<RuntimeCall>(instructions: &[Instruction<RuntimeCall>]) -> Weight {
+		B::barrier_weight(instructions).saturating_mul(N as u64)
+	}
+}
+
+/// How a barrier accepted a message, as reported by `ShouldExecuteReport`.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Accepted {
+	/// The message was accepted without changes to its `Properties`.
+	Accept,
+	/// The message was accepted and its `Properties` were changed, e.g. weight credit was taken.
+	AcceptWithChanges,
+}
+
+/// Extension of `ShouldExecute` reporting whether a passing barrier changed the `Properties` of
+/// the message. Implemented for all `ShouldExecute` barriers.
+///
+/// Only `weight_credit`, `message_id`, `risk_score` and `suggested_weight` are compared; the
+/// bookkeeping of barrier tuples, i.e. `barriers_evaluated` and `last_rejection`, is not considered
+/// a change.
+pub trait ShouldExecuteReport {
+	/// Like `ShouldExecute::should_execute`, but reports how the message was accepted.
+	fn should_execute_report<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<Accepted, ProcessMessageError>;
+}
+
+impl<B: ShouldExecute> ShouldExecuteReport for B {
+	fn should_execute_report<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<Accepted, ProcessMessageError> {
+		let observed = |properties: &Properties| {
+			(
+				properties.weight_credit,
+				properties.message_id,
+				properties.risk_score,
+				properties.suggested_weight,
+			)
+		};
+		let prior = observed(properties);
+		B::should_execute(origin, instructions, max_weight, properties)?;
+		if prior == observed(properties) {
+			Ok(Accepted::Accept)
+		} else {
+			Ok(Accepted::AcceptWithChanges)
+		}
+	}
 }
 
 /// Trait to determine whether the execution engine is suspended from executing a given XCM.
@@ -120,6 +342,16 @@ impl CheckSuspension for Tuple {
 	) -> bool {
 		for_tuples!( #(
 			if Tuple::is_suspended(origin, instruction, max_weight, properties) {
+				let barrier = core::any::type_name::<Tuple>();
+				tracing::debug!(
+					target: "xcm::check_suspension",
+					?origin,
+					?instruction,
+					?max_weight,
+					?properties,
+					%barrier,
+					"suspended by barrier",
+				);
 				return true
 			}
 		)* );
@@ -148,6 +380,14 @@ pub trait DenyExecution {
 		max_weight: Weight,
 		properties: &mut Properties,
 	) -> Result<(), ProcessMessageError>;
+
+	/// Returns the weight of evaluating this barrier against `instructions`, which the executor
+	/// adds to the weight consumed by the message.
+	///
+	/// Zero by default; tuples sum the weights of all of their elements.
+	fn barrier_weight<RuntimeCall>(_instructions: &[Instruction<RuntimeCall>]) -> Weight {
+		Weight::zero()
+	}
 }
 
 #[impl_trait_for_tuples::impl_for_tuples(10)]
@@ -160,8 +400,12 @@ impl DenyExecution for Tuple {
 	) -> Result<(), ProcessMessageError> {
 		for_tuples!( #(
             let barrier = core::any::type_name::<Tuple>();
-            match Tuple::deny_execution(origin, instructions, max_weight, properties) {
+            let result = Tuple::deny_execution(origin, instructions, max_weight, properties);
+            #[cfg(feature = "std")]
+            record_barrier_decision(barrier, result);
+            match result {
                 Err(error) => {
+                    properties.last_rejection = Some(barrier);
                     tracing::error!(
                         target: "xcm::deny_execution",
                         ?origin,
@@ -190,4 +434,427 @@ impl DenyExecution for Tuple {
 
 		Ok(())
 	}
+
+	fn barrier_weight<RuntimeCall>(instructions: &[Instruction<RuntimeCall>]) -> Weight {
+		let mut weight = Weight::zero();
+		for_tuples!( #( weight.saturating_accrue(Tuple::barrier_weight(instructions)); )* );
+		weight
+	}
+}
+
+/// A barrier verdict against a message, mapping onto the subset of `ProcessMessageError` used by
+/// barriers.
+///
+/// Every verdict but `RateLimited` is recovered when converting the error back.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum BarrierError {
+	/// The message is not allowed to execute. Maps to `ProcessMessageError::Unsupported`.
+	NotAllowed,
+	/// Execution of the message is suspended for now. Maps to `ProcessMessageError::Yield`.
+	Suspended,
+	/// The message requires more weight than allowed. Maps to `ProcessMessageError::Overweight`.
+	Overweight(Weight),
+	/// The message is malformed. Maps to `ProcessMessageError::BadFormat`.
+	Malformed,
+	/// The origin exceeded the rate of messages allowed to it. Maps to
+	/// `ProcessMessageError::Yield`, so once lowered, rate limiting is indistinguishable from
+	/// suspension and is read back as `Suspended`.
+	RateLimited,
+}
+
+impl From<BarrierError> for ProcessMessageError {
+	fn from(error: BarrierError) -> Self {
+		match error {
+			BarrierError::NotAllowed => ProcessMessageError::Unsupported,
+			BarrierError::Suspended | BarrierError::RateLimited => ProcessMessageError::Yield,
+			BarrierError::Overweight(weight) => ProcessMessageError::Overweight(weight),
+			BarrierError::Malformed => ProcessMessageError::BadFormat,
+		}
+	}
+}
+
+impl TryFrom<ProcessMessageError> for BarrierError {
+	/// The error is handed back if it has no barrier counterpart.
+	type Error = ProcessMessageError;
+
+	fn try_from(error: ProcessMessageError) -> Result<Self, Self::Error> {
+		match error {
+			ProcessMessageError::Unsupported => Ok(BarrierError::NotAllowed),
+			ProcessMessageError::Yield => Ok(BarrierError::Suspended),
+			ProcessMessageError::Overweight(weight) => Ok(BarrierError::Overweight(weight)),
+			ProcessMessageError::BadFormat => Ok(BarrierError::Malformed),
+			ProcessMessageError::Corrupt | ProcessMessageError::StackLimitReached => Err(error),
+		}
+	}
+}
+
+/// Computes the origin in effect after the leading origin-altering instructions of `instructions`,
+/// i.e. `DescendOrigin`, `ClearOrigin` and `UniversalOrigin`, stopping at the first other
+/// instruction. `UniversalLocation` is the location of the local consensus system, against which
+/// `UniversalOrigin` is resolved.
+///
+/// Returns `None` if the origin was cleared, and an error if it is altered in a way which would
+/// fail on execution, e.g. by descending from a cleared origin. As with `WithComputedOrigin`, the
+/// validity of `UniversalOrigin` itself is left to the executor.
+pub fn effective_origin<UniversalLocation: Get<InteriorLocation>, Call>(
+	origin: &Location,
+	instructions: &[Instruction<Call>],
+) -> Result<Option<Location>, ProcessMessageError> {
+	let mut effective = Some(origin.clone());
+	for instruction in instructions {
+		match instruction {
+			Instruction::DescendOrigin(interior) => {
+				let origin = effective.as_mut().ok_or(ProcessMessageError::Unsupported)?;
+				origin
+					.append_with(interior.clone())
+					.map_err(|_| ProcessMessageError::Unsupported)?;
+			},
+			Instruction::ClearOrigin => effective = None,
+			Instruction::UniversalOrigin(new_global) => {
+				effective.as_ref().ok_or(ProcessMessageError::Unsupported)?;
+				effective =
+					Some(Junctions::from([*new_global]).relative_to(&UniversalLocation::get()));
+			},
+			_ => break,
+		}
+	}
+	Ok(effective)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::{Arc, Mutex};
+
+	/// Collects the output of a `tracing` subscriber.
+	#[derive(Clone, Default)]
+	struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+	impl std::io::Write for CapturedLogs {
+		fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+			self.0.lock().unwrap().write(buf)
+		}
+		fn flush(&mut self) -> std::io::Result<()> {
+			Ok(())
+		}
+	}
+
+	struct NeverSuspended;
+	impl CheckSuspension for NeverSuspended {
+		fn is_suspended<Call>(
+			_origin: &Location,
+			_instructions: &mut [Instruction<Call>],
+			_max_weight: Weight,
+			_properties: &mut Properties,
+		) -> bool {
+			false
+		}
+	}
+
+	struct AlwaysSuspended;
+	impl CheckSuspension for AlwaysSuspended {
+		fn is_suspended<Call>(
+			_origin: &Location,
+			_instructions: &mut [Instruction<Call>],
+			_max_weight: Weight,
+			_properties: &mut Properties,
+		) -> bool {
+			true
+		}
+	}
+
+	#[derive(Default)]
+	struct CollectRejections(Vec<&'static str>);
+	impl BarrierEvent for CollectRejections {
+		fn on_rejection(&mut self, barrier: &'static str, _error: ProcessMessageError) {
+			self.0.push(barrier);
+		}
+	}
+
+	#[test]
+	fn barrier_rejections_are_reported_to_installed_sink() {
+		// no sink installed: reporting is a no-op
+		report_barrier_rejection("ignored", ProcessMessageError::Unsupported);
+
+		let mut sink = CollectRejections::default();
+		with_barrier_event_sink(&mut sink, || {
+			report_barrier_rejection("first", ProcessMessageError::Unsupported);
+			report_barrier_rejection("second", ProcessMessageError::BadFormat);
+		});
+		assert_eq!(sink.0, vec!["first", "second"]);
+	}
+
+	/// Barrier rejecting everything, whose evaluation costs `REF_TIME` per instruction.
+	struct Costly<const REF_TIME: u64>;
+	impl<const REF_TIME: u64> ShouldExecute for Costly<REF_TIME> {
+		fn should_execute<Call>(
+			_origin: &Location,
+			_instructions: &mut [Instruction<Call>],
+			_max_weight: Weight,
+			_properties: &mut Properties,
+		) -> Result<(), ProcessMessageError> {
+			Err(ProcessMessageError::Unsupported)
+		}
+
+		fn barrier_weight<Call>(instructions: &[Instruction<Call>]) -> Weight {
+			Weight::from_parts(REF_TIME * instructions.len() as u64, 0)
+		}
+	}
+
+	struct Free;
+	impl ShouldExecute for Free {
+		fn should_execute<Call>(
+			_origin: &Location,
+			_instructions: &mut [Instruction<Call>],
+			_max_weight: Weight,
+			_properties: &mut Properties,
+		) -> Result<(), ProcessMessageError> {
+			Ok(())
+		}
+	}
+
+	#[test]
+	fn barrier_weight_sums_tuple_elements() {
+		let instructions: [Instruction<()>; 2] =
+			[Instruction::ClearOrigin, Instruction::ClearOrigin];
+
+		assert_eq!(Free::barrier_weight(&instructions), Weight::zero());
+		assert_eq!(Costly::<5>::barrier_weight(&instructions), Weight::from_parts(10, 0));
+		assert_eq!(<() as ShouldExecute>::barrier_weight(&instructions), Weight::zero());
+		assert_eq!(
+			<(Costly<5>, Free, Costly<7>)>::barrier_weight(&instructions),
+			Weight::from_parts(24, 0)
+		);
+		assert_eq!(
+			<(Costly<1>, (Costly<2>, Costly<3>))>::barrier_weight(&instructions),
+			Weight::from_parts(12, 0)
+		);
+		assert_eq!(<[Costly<5>; 3]>::barrier_weight(&instructions), Weight::from_parts(30, 0));
+	}
+
+	#[test]
+	fn derive_should_work_within_the_executor() {
+		#[derive(crate::traits::ShouldExecute)]
+		#[allow(dead_code)]
+		struct Derived(Costly<5>, Free);
+
+		let mut instructions = [Instruction::<()>::ClearOrigin, Instruction::ClearOrigin];
+		assert_eq!(
+			Derived::barrier_weight(&instructions),
+			<(Costly<5>, Free)>::barrier_weight(&instructions)
+		);
+		let mut properties = Properties::new(Weight::zero(), None);
+		assert_eq!(
+			Derived::should_execute(
+				&Location::parent(),
+				&mut instructions,
+				Weight::zero(),
+				&mut properties
+			),
+			Ok(())
+		);
+		assert_eq!(properties.barriers_evaluated, 2);
+	}
+
+	struct TakeCredit;
+	impl ShouldExecute for TakeCredit {
+		fn should_execute<Call>(
+			_origin: &Location,
+			_instructions: &mut [Instruction<Call>],
+			max_weight: Weight,
+			properties: &mut Properties,
+		) -> Result<(), ProcessMessageError> {
+			properties.weight_credit = properties
+				.weight_credit
+				.checked_sub(&max_weight)
+				.ok_or(ProcessMessageError::Overweight(max_weight))?;
+			Ok(())
+		}
+	}
+
+	struct SuggestWeight;
+	impl ShouldExecute for SuggestWeight {
+		fn should_execute<Call>(
+			_origin: &Location,
+			_instructions: &mut [Instruction<Call>],
+			max_weight: Weight,
+			properties: &mut Properties,
+		) -> Result<(), ProcessMessageError> {
+			properties.suggested_weight = Some(max_weight);
+			Ok(())
+		}
+	}
+
+	#[test]
+	fn should_execute_report_distinguishes_mutating_passes() {
+		let report = |max_weight: Weight,
+		              f: fn(
+			&mut [Instruction<()>],
+			Weight,
+			&mut Properties,
+		) -> Result<Accepted, ProcessMessageError>| {
+			let mut properties = Properties::new(Weight::from_parts(10, 10), None);
+			f(&mut [Instruction::ClearOrigin], max_weight, &mut properties)
+		};
+
+		// pure pass, including the bookkeeping of tuples
+		assert_eq!(
+			report(Weight::zero(), |i, w, p| Free::should_execute_report(
+				&Location::parent(),
+				i,
+				w,
+				p
+			)),
+			Ok(Accepted::Accept)
+		);
+		assert_eq!(
+			report(Weight::zero(), |i, w, p| {
+				<(Costly<1>, Free)>::should_execute_report(&Location::parent(), i, w, p)
+			}),
+			Ok(Accepted::Accept)
+		);
+		// mutating pass
+		assert_eq!(
+			report(Weight::from_parts(5, 5), |i, w, p| {
+				TakeCredit::should_execute_report(&Location::parent(), i, w, p)
+			}),
+			Ok(Accepted::AcceptWithChanges)
+		);
+		assert_eq!(
+			report(Weight::from_parts(5, 5), |i, w, p| {
+				SuggestWeight::should_execute_report(&Location::parent(), i, w, p)
+			}),
+			Ok(Accepted::AcceptWithChanges)
+		);
+		// a mutation which happens to be a no-op is no change
+		assert_eq!(
+			report(Weight::zero(), |i, w, p| TakeCredit::should_execute_report(
+				&Location::parent(),
+				i,
+				w,
+				p
+			)),
+			Ok(Accepted::Accept)
+		);
+		// rejection
+		assert_eq!(
+			report(Weight::from_parts(20, 20), |i, w, p| {
+				TakeCredit::should_execute_report(&Location::parent(), i, w, p)
+			}),
+			Err(ProcessMessageError::Overweight(Weight::from_parts(20, 20)))
+		);
+	}
+
+	#[test]
+	fn barrier_error_round_trips() {
+		for (barrier_error, error) in [
+			(BarrierError::NotAllowed, ProcessMessageError::Unsupported),
+			(BarrierError::Suspended, ProcessMessageError::Yield),
+			(
+				BarrierError::Overweight(Weight::from_parts(1, 2)),
+				ProcessMessageError::Overweight(Weight::from_parts(1, 2)),
+			),
+			(BarrierError::Malformed, ProcessMessageError::BadFormat),
+		] {
+			assert_eq!(ProcessMessageError::from(barrier_error), error);
+			assert_eq!(BarrierError::try_from(error), Ok(barrier_error));
+		}
+		for error in [ProcessMessageError::Corrupt, ProcessMessageError::StackLimitReached] {
+			assert_eq!(BarrierError::try_from(error), Err(error));
+		}
+	}
+
+	#[test]
+	fn effective_origin_should_work() {
+		use xcm::latest::prelude::*;
+		frame_support::parameter_types! {
+			pub UniversalLocation: InteriorLocation = [GlobalConsensus(Polkadot), Parachain(1000)].into();
+		}
+		let effective = |instructions: Vec<Instruction<()>>| {
+			effective_origin::<UniversalLocation, ()>(&Location::parent(), &instructions)
+		};
+
+		// no prefix
+		assert_eq!(effective(vec![]), Ok(Some(Location::parent())));
+		assert_eq!(effective(vec![Trap(0)]), Ok(Some(Location::parent())));
+		// descend
+		assert_eq!(
+			effective(vec![
+				DescendOrigin(Parachain(1).into()),
+				DescendOrigin(PalletInstance(2).into())
+			]),
+			Ok(Some((Parent, Parachain(1), PalletInstance(2)).into()))
+		);
+		// clear
+		assert_eq!(effective(vec![ClearOrigin]), Ok(None));
+		assert_eq!(
+			effective(vec![ClearOrigin, DescendOrigin(Parachain(1).into())]),
+			Err(ProcessMessageError::Unsupported)
+		);
+		// universal
+		assert_eq!(
+			effective(vec![UniversalOrigin(GlobalConsensus(Kusama))]),
+			Ok(Some((Parent, Parent, GlobalConsensus(Kusama)).into()))
+		);
+		// mixed, stopping at the first non-origin instruction
+		assert_eq!(
+			effective(vec![
+				UniversalOrigin(GlobalConsensus(Kusama)),
+				DescendOrigin(Parachain(1).into()),
+				Trap(0),
+				ClearOrigin,
+			]),
+			Ok(Some((Parent, Parent, GlobalConsensus(Kusama), Parachain(1)).into()))
+		);
+		assert_eq!(
+			effective(vec![DescendOrigin(Parachain(1).into()), ClearOrigin, Trap(0)]),
+			Ok(None)
+		);
+	}
+
+	#[test]
+	fn check_suspension_logs_suspending_barrier() {
+		let logs = CapturedLogs::default();
+		let subscriber = tracing_subscriber::fmt()
+			.with_max_level(tracing::Level::DEBUG)
+			.with_writer({
+				let logs = logs.clone();
+				move || logs.clone()
+			})
+			.finish();
+
+		let suspended = tracing::subscriber::with_default(subscriber, || {
+			<(NeverSuspended, AlwaysSuspended)>::is_suspended::<()>(
+				&Location::parent(),
+				&mut [],
+				Weight::zero(),
+				&mut Properties::new(Weight::zero(), None),
+			)
+		});
+		assert!(suspended);
+
+		let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+		assert!(logs.contains("suspended by barrier"));
+		assert!(logs.contains(core::any::type_name::<AlwaysSuspended>()));
+		assert!(!logs.contains(core::any::type_name::<NeverSuspended>()));
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn properties_serde_round_trip_works() {
+		for properties in [
+			Properties::new(Weight::zero(), None),
+			Properties {
+				weight_credit: Weight::from_parts(1_000, 2_000),
+				message_id: Some([7; 32]),
+				barriers_evaluated: 3,
+				last_rejection: None,
+				risk_score: 42,
+				suggested_weight: Some(Weight::from_parts(500, 600)),
+			},
+		] {
+			let serialized = serde_json::to_string(&properties).unwrap();
+			let deserialized: Properties = serde_json::from_str(&serialized).unwrap();
+			assert_eq!(deserialized, properties);
+		}
+	}
 }